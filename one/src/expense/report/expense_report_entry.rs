@@ -0,0 +1,35 @@
+use std::fmt::{Display, Formatter};
+
+/// A single expense report entry, along with where it appeared in the report.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExpenseReportEntry {
+    /// Zero-based index of the line of the expense report holding this entry.
+    pub index: usize,
+    /// Amount recorded by this entry.
+    pub value: i64,
+}
+
+impl ExpenseReportEntry {
+    /// Pairs each of the `expense_report_entry_values` with its index.
+    pub fn index_all(expense_report_entry_values: &[i64]) -> Vec<ExpenseReportEntry> {
+        expense_report_entry_values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| ExpenseReportEntry {
+                index,
+                value: *value,
+            })
+            .collect()
+    }
+
+    /// One-based number of the line of the expense report holding this entry.
+    pub fn line_number(&self) -> usize {
+        self.index + 1
+    }
+}
+
+impl Display for ExpenseReportEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (line {})", self.value, self.line_number())
+    }
+}
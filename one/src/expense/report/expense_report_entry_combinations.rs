@@ -0,0 +1,264 @@
+use super::index_combinations::IndexCombinations;
+use super::ExpenseReportEntry;
+use std::collections::HashMap;
+
+/// Lazily iterates through every combination of distinct expense report
+/// entries that sums to a target.
+///
+/// Each combination, ordered by index, is made up of a lower half holding its
+/// first `combination_size / 2` entries and an upper half holding the rest.
+/// Every lower half is tabulated by its sum up front; then upper halves are
+/// visited one at a time, each pairing up with the lower halves that have a
+/// complementary sum and end before it begins. Since a combination splits into
+/// halves in exactly one way, no combination is yielded twice.
+pub struct ExpenseReportEntryCombinations<'a> {
+    /// Values of every entry of the expense report.
+    expense_report_entries: &'a [i64],
+    /// Lower halves grouped by sum, each group ordered by `half_end(...)`.
+    lower_halves_by_sum: HashMap<i128, Vec<Vec<usize>>>,
+    /// Upper halves that have yet to be visited.
+    upper_halves: IndexCombinations,
+    /// The upper half being visited, the sum its lower half needs, and the
+    /// position of the next lower half to pair it up with.
+    current_upper_half: Option<(Vec<usize>, i128, usize)>,
+    /// What every yielded combination sums to.
+    target: i64,
+}
+
+impl<'a> ExpenseReportEntryCombinations<'a> {
+    /// Creates a new `ExpenseReportEntryCombinations`.
+    ///
+    /// `expense_report_entries`:   values of every entry of the expense
+    ///                             report.
+    ///
+    /// `combination_size`:         how many entries make up each combination.
+    ///
+    /// `target`:                   what every combination sums to.
+    pub fn new(
+        expense_report_entries: &'a [i64],
+        combination_size: usize,
+        target: i64,
+    ) -> ExpenseReportEntryCombinations<'a> {
+        let lower_half_size = combination_size / 2;
+        let upper_half_size = combination_size - lower_half_size;
+
+        let mut lower_halves_by_sum: HashMap<i128, Vec<Vec<usize>>> = HashMap::new();
+        for lower_half in IndexCombinations::new(expense_report_entries.len(), lower_half_size) {
+            lower_halves_by_sum
+                .entry(sum_of(expense_report_entries, &lower_half))
+                .or_default()
+                .push(lower_half);
+        }
+        for lower_halves in lower_halves_by_sum.values_mut() {
+            lower_halves.sort_by_key(|lower_half| half_end(lower_half));
+        }
+
+        ExpenseReportEntryCombinations {
+            expense_report_entries,
+            lower_halves_by_sum,
+            upper_halves: IndexCombinations::new(expense_report_entries.len(), upper_half_size),
+            current_upper_half: None,
+            target,
+        }
+    }
+}
+
+impl<'a> Iterator for ExpenseReportEntryCombinations<'a> {
+    type Item = Vec<ExpenseReportEntry>;
+
+    // Advances to the next combination summing to the target.
+    fn next(&mut self) -> Option<Vec<ExpenseReportEntry>> {
+        let expense_report_entries = self.expense_report_entries;
+
+        loop {
+            if let Some((upper_half, complement, next_lower_half_position)) =
+                &mut self.current_upper_half
+            {
+                let upper_half_start = half_start(upper_half, expense_report_entries.len());
+                let lower_half = self
+                    .lower_halves_by_sum
+                    .get(complement)
+                    .and_then(|lower_halves| lower_halves.get(*next_lower_half_position))
+                    .filter(|lower_half| half_end(lower_half) <= upper_half_start);
+
+                if let Some(lower_half) = lower_half {
+                    *next_lower_half_position += 1;
+
+                    return Some(
+                        lower_half
+                            .iter()
+                            .chain(upper_half.iter())
+                            .map(|index| ExpenseReportEntry {
+                                index: *index,
+                                value: expense_report_entries[*index],
+                            })
+                            .collect(),
+                    );
+                }
+            }
+
+            let upper_half = self.upper_halves.next()?;
+            let complement = self.target as i128 - sum_of(expense_report_entries, &upper_half);
+
+            self.current_upper_half = Some((upper_half, complement, 0));
+        }
+    }
+}
+
+/// Counts every combination of `combination_size` distinct expense report
+/// entries that sums to `target`, without assembling any of them.
+///
+/// Follows the same approach as `ExpenseReportEntryCombinations`, but only
+/// remembers where each lower half ends, so that the lower halves compatible
+/// with an upper half can be counted with a binary search.
+pub fn count_expense_report_entry_combinations(
+    expense_report_entries: &[i64],
+    combination_size: usize,
+    target: i64,
+) -> usize {
+    let lower_half_size = combination_size / 2;
+    let upper_half_size = combination_size - lower_half_size;
+
+    let mut lower_half_ends_by_sum: HashMap<i128, Vec<usize>> = HashMap::new();
+    for lower_half in IndexCombinations::new(expense_report_entries.len(), lower_half_size) {
+        lower_half_ends_by_sum
+            .entry(sum_of(expense_report_entries, &lower_half))
+            .or_default()
+            .push(half_end(&lower_half));
+    }
+    for lower_half_ends in lower_half_ends_by_sum.values_mut() {
+        lower_half_ends.sort_unstable();
+    }
+
+    IndexCombinations::new(expense_report_entries.len(), upper_half_size)
+        .map(|upper_half| {
+            let upper_half_start = half_start(&upper_half, expense_report_entries.len());
+
+            lower_half_ends_by_sum
+                .get(&(target as i128 - sum_of(expense_report_entries, &upper_half)))
+                .map(|lower_half_ends| {
+                    lower_half_ends
+                        .partition_point(|lower_half_end| *lower_half_end <= upper_half_start)
+                })
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// Index immediately following the last of the `half`'s indices, or `0` if
+/// `half` is empty.
+fn half_end(half: &[usize]) -> usize {
+    half.last().map(|index| index + 1).unwrap_or(0)
+}
+
+/// First of the `half`'s indices, or `length` if `half` is empty.
+fn half_start(half: &[usize], length: usize) -> usize {
+    half.first().copied().unwrap_or(length)
+}
+
+/// Adds up the `expense_report_entries` at the given `indices`.
+///
+/// Values are widened to `i128` before being added up, so that the sum cannot
+/// overflow.
+fn sum_of(expense_report_entries: &[i64], indices: &[usize]) -> i128 {
+    indices
+        .iter()
+        .map(|index| expense_report_entries[*index] as i128)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Expense report with repeated values and negative entries, so that many
+    /// combinations share the same sum.
+    const EXPENSE_REPORT_ENTRIES: [i64; 9] = [3, -1, 2, 3, 0, 5, -1, 4, 2];
+
+    /// Returns the indices of every combination of `combination_size` entries
+    /// of `EXPENSE_REPORT_ENTRIES` that sums to `target`, found by checking
+    /// every combination one by one.
+    fn brute_force_index_combinations(combination_size: usize, target: i64) -> Vec<Vec<usize>> {
+        IndexCombinations::new(EXPENSE_REPORT_ENTRIES.len(), combination_size)
+            .filter(|indices| sum_of(&EXPENSE_REPORT_ENTRIES, indices) == target as i128)
+            .collect()
+    }
+
+    #[test]
+    fn yields_every_combination_exactly_once() {
+        for combination_size in 1..=5 {
+            for target in -2..=20 {
+                let index_combinations = ExpenseReportEntryCombinations::new(
+                    &EXPENSE_REPORT_ENTRIES,
+                    combination_size,
+                    target,
+                )
+                .map(|combination| {
+                    combination
+                        .iter()
+                        .map(|expense_report_entry| expense_report_entry.index)
+                        .collect::<Vec<usize>>()
+                })
+                .collect::<Vec<Vec<usize>>>();
+                let distinct_index_combinations = index_combinations
+                    .iter()
+                    .cloned()
+                    .collect::<BTreeSet<Vec<usize>>>();
+
+                assert_eq!(
+                    distinct_index_combinations.len(),
+                    index_combinations.len(),
+                    "size {}, target {}",
+                    combination_size,
+                    target
+                );
+                assert_eq!(
+                    distinct_index_combinations,
+                    brute_force_index_combinations(combination_size, target)
+                        .into_iter()
+                        .collect::<BTreeSet<Vec<usize>>>(),
+                    "size {}, target {}",
+                    combination_size,
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn yields_combinations_of_entries_in_order() {
+        for combination in ExpenseReportEntryCombinations::new(&EXPENSE_REPORT_ENTRIES, 4, 8) {
+            assert!(combination
+                .windows(2)
+                .all(|window| window[0].index < window[1].index));
+            assert!(combination.iter().all(|expense_report_entry| {
+                EXPENSE_REPORT_ENTRIES[expense_report_entry.index] == expense_report_entry.value
+            }));
+        }
+    }
+
+    #[test]
+    fn counts_as_many_combinations_as_are_yielded() {
+        for combination_size in 0..=5 {
+            for target in -2..=20 {
+                assert_eq!(
+                    count_expense_report_entry_combinations(
+                        &EXPENSE_REPORT_ENTRIES,
+                        combination_size,
+                        target
+                    ),
+                    ExpenseReportEntryCombinations::new(
+                        &EXPENSE_REPORT_ENTRIES,
+                        combination_size,
+                        target
+                    )
+                    .count(),
+                    "size {}, target {}",
+                    combination_size,
+                    target
+                );
+            }
+        }
+    }
+}
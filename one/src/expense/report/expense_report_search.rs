@@ -0,0 +1,345 @@
+use super::index_combinations::IndexCombinations;
+use super::ExpenseReportEntry;
+use std::collections::HashMap;
+
+/// Finds `combination_size` distinct expense report entries that sum to
+/// `target`.
+///
+/// The search strategy is picked based on `combination_size`:
+///
+/// | combination size | strategy                          | time           |
+/// |------------------|-----------------------------------|----------------|
+/// | 0 or 1           | linear scan                       | O(n)           |
+/// | 2                | hashing                           | O(n)           |
+/// | 3                | sorting, then sweeping two points | O(n²)          |
+/// | 4 and up         | meet-in-the-middle                | O(n^⌈k / 2⌉)   |
+///
+/// Returned entries are ordered by index.
+pub fn find_expense_report_entry_combination(
+    expense_report_entries: &[i64],
+    combination_size: usize,
+    target: i64,
+) -> Option<Vec<ExpenseReportEntry>> {
+    let indexed_expense_report_entries = ExpenseReportEntry::index_all(expense_report_entries);
+
+    let mut combination = match combination_size {
+        0 => {
+            if target == 0 {
+                Some(vec![])
+            } else {
+                None
+            }
+        }
+        1 => indexed_expense_report_entries
+            .into_iter()
+            .find(|expense_report_entry| expense_report_entry.value == target)
+            .map(|expense_report_entry| vec![expense_report_entry]),
+        2 => find_pair(&indexed_expense_report_entries, target),
+        3 => find_trio(indexed_expense_report_entries, target),
+        _ => find_combination_by_meeting_in_the_middle(
+            &indexed_expense_report_entries,
+            combination_size,
+            target,
+        ),
+    }?;
+
+    combination.sort_by_key(|expense_report_entry| expense_report_entry.index);
+
+    Some(combination)
+}
+
+/// Finds a pair of distinct expense report entries that sum to `target`.
+///
+/// Runs in linear time by remembering the index of every entry seen so far,
+/// and checking whether the complement of each new entry has already turned
+/// up. Since only earlier entries are remembered, an entry is never paired
+/// with itself, while two separate entries sharing a value still pair up.
+fn find_pair(
+    expense_report_entries: &[ExpenseReportEntry],
+    target: i64,
+) -> Option<Vec<ExpenseReportEntry>> {
+    let mut seen_expense_report_entry_indices =
+        HashMap::with_capacity(expense_report_entries.len());
+
+    expense_report_entries
+        .iter()
+        .find_map(|expense_report_entry| {
+            // A complement that does not fit in an `i64` cannot be an entry.
+            let complement = target.checked_sub(expense_report_entry.value);
+            let complement_index = complement
+                .and_then(|complement| seen_expense_report_entry_indices.get(&complement).copied());
+
+            match (complement, complement_index) {
+                (Some(complement), Some(complement_index)) => Some(vec![
+                    ExpenseReportEntry {
+                        index: complement_index,
+                        value: complement,
+                    },
+                    *expense_report_entry,
+                ]),
+                _ => {
+                    seen_expense_report_entry_indices
+                        .entry(expense_report_entry.value)
+                        .or_insert(expense_report_entry.index);
+
+                    None
+                }
+            }
+        })
+}
+
+/// Finds a trio of distinct expense report entries that sum to `target`.
+///
+/// Sorts the entries by value, then sweeps two pointers inwards over the
+/// entries following each candidate first entry.
+fn find_trio(
+    mut expense_report_entries: Vec<ExpenseReportEntry>,
+    target: i64,
+) -> Option<Vec<ExpenseReportEntry>> {
+    expense_report_entries.sort_by_key(|expense_report_entry| expense_report_entry.value);
+
+    for (first_position, first_entry) in expense_report_entries.iter().enumerate() {
+        let mut lower_position = first_position + 1;
+        let mut upper_position = expense_report_entries.len().saturating_sub(1);
+
+        while lower_position < upper_position {
+            let lower_entry = expense_report_entries[lower_position];
+            let upper_entry = expense_report_entries[upper_position];
+            let sum =
+                first_entry.value as i128 + lower_entry.value as i128 + upper_entry.value as i128;
+
+            if sum < target as i128 {
+                lower_position += 1;
+            } else if sum > target as i128 {
+                upper_position -= 1;
+            } else {
+                return Some(vec![*first_entry, lower_entry, upper_entry]);
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds `combination_size` distinct expense report entries that sum to
+/// `target` by splitting every combination in two.
+///
+/// Each combination, ordered by index, is made up of a lower half holding its
+/// first `combination_size / 2` entries and an upper half holding the rest.
+/// Every lower half is tabulated by its sum; then every upper half looks up
+/// the lower halves with a complementary sum that end before it begins.
+fn find_combination_by_meeting_in_the_middle(
+    expense_report_entries: &[ExpenseReportEntry],
+    combination_size: usize,
+    target: i64,
+) -> Option<Vec<ExpenseReportEntry>> {
+    let lower_half_size = combination_size / 2;
+    let upper_half_size = combination_size - lower_half_size;
+
+    // Only the lower half ending soonest matters for each sum, since it is
+    // compatible with the most upper halves.
+    let mut lower_halves_by_sum: HashMap<i128, Vec<usize>> = HashMap::new();
+    for lower_half in IndexCombinations::new(expense_report_entries.len(), lower_half_size) {
+        let sum = sum_of(expense_report_entries, &lower_half);
+
+        lower_halves_by_sum
+            .entry(sum)
+            .and_modify(|earliest_lower_half| {
+                if lower_half.last() < earliest_lower_half.last() {
+                    *earliest_lower_half = lower_half.clone();
+                }
+            })
+            .or_insert(lower_half);
+    }
+
+    IndexCombinations::new(expense_report_entries.len(), upper_half_size).find_map(|upper_half| {
+        let complement = target as i128 - sum_of(expense_report_entries, &upper_half);
+
+        lower_halves_by_sum
+            .get(&complement)
+            .filter(|lower_half| lower_half.last() < upper_half.first())
+            .map(|lower_half| {
+                lower_half
+                    .iter()
+                    .chain(upper_half.iter())
+                    .map(|index| expense_report_entries[*index])
+                    .collect()
+            })
+    })
+}
+
+/// Adds up the values of the `expense_report_entries` at the given `indices`.
+///
+/// Values are widened to `i128` before being added up, so that the sum cannot
+/// overflow.
+fn sum_of(expense_report_entries: &[ExpenseReportEntry], indices: &[usize]) -> i128 {
+    indices
+        .iter()
+        .map(|index| expense_report_entries[*index].value as i128)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example expense report from https://adventofcode.com/2020/day/1.
+    const EXAMPLE_EXPENSE_REPORT_ENTRIES: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    /// Returns the indices of the combination found, if any.
+    fn find_indices(
+        expense_report_entries: &[i64],
+        combination_size: usize,
+        target: i64,
+    ) -> Option<Vec<usize>> {
+        find_expense_report_entry_combination(expense_report_entries, combination_size, target).map(
+            |combination| {
+                combination
+                    .iter()
+                    .map(|expense_report_entry| expense_report_entry.index)
+                    .collect()
+            },
+        )
+    }
+
+    /// Asserts that a combination is found exactly when checking every
+    /// combination one by one turns one up, and that it is made up of
+    /// `combination_size` distinct entries summing to `target`.
+    fn assert_agrees_with_brute_force(
+        expense_report_entries: &[i64],
+        combination_size: usize,
+        target: i64,
+    ) {
+        let is_reachable = IndexCombinations::new(expense_report_entries.len(), combination_size)
+            .any(|indices| {
+                indices
+                    .iter()
+                    .map(|index| expense_report_entries[*index] as i128)
+                    .sum::<i128>()
+                    == target as i128
+            });
+        let combination =
+            find_expense_report_entry_combination(expense_report_entries, combination_size, target);
+
+        assert_eq!(combination.is_some(), is_reachable, "target {}", target);
+        if let Some(combination) = combination {
+            assert_eq!(combination.len(), combination_size);
+            assert!(combination
+                .windows(2)
+                .all(|window| window[0].index < window[1].index));
+            assert!(combination.iter().all(|expense_report_entry| {
+                expense_report_entries[expense_report_entry.index] == expense_report_entry.value
+            }));
+            assert_eq!(
+                combination
+                    .iter()
+                    .map(|expense_report_entry| expense_report_entry.value as i128)
+                    .sum::<i128>(),
+                target as i128
+            );
+        }
+    }
+
+    #[test]
+    fn finds_empty_combination_only_for_zero() {
+        assert_eq!(
+            find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 0, 0),
+            Some(vec![])
+        );
+        assert_eq!(find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 0, 2020), None);
+    }
+
+    #[test]
+    fn finds_single_entry() {
+        assert_eq!(
+            find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 1, 299),
+            Some(vec![3])
+        );
+        assert_eq!(find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 1, 2020), None);
+    }
+
+    #[test]
+    fn finds_example_pair() {
+        assert_eq!(
+            find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 2, 2020),
+            Some(vec![0, 3])
+        );
+        assert_eq!(find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 2, 1), None);
+    }
+
+    #[test]
+    fn finds_example_trio() {
+        assert_eq!(
+            find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 3, 2020),
+            Some(vec![1, 2, 4])
+        );
+        assert_eq!(find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 3, 3), None);
+    }
+
+    #[test]
+    fn meets_in_the_middle() {
+        assert_eq!(
+            find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 6, 5496),
+            Some(vec![0, 1, 2, 3, 4, 5])
+        );
+        assert_eq!(find_indices(&EXAMPLE_EXPENSE_REPORT_ENTRIES, 7, 5496), None);
+
+        for combination_size in 4..=6 {
+            for target in 1000..=5500 {
+                assert_agrees_with_brute_force(
+                    &EXAMPLE_EXPENSE_REPORT_ENTRIES,
+                    combination_size,
+                    target,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pairs_separate_entries_with_the_same_value() {
+        assert_eq!(find_indices(&[1010, 5], 2, 2020), None);
+        assert_eq!(find_indices(&[1010, 5, 1010], 2, 2020), Some(vec![0, 2]));
+        assert_eq!(find_indices(&[1010, 5, 1010], 3, 3030), None);
+        assert_eq!(
+            find_indices(&[1010, 1010, 5, 1010, 1010], 4, 4040),
+            Some(vec![0, 1, 3, 4])
+        );
+    }
+
+    #[test]
+    fn finds_negative_entries() {
+        let expense_report_entries = [-5, 7, 3, 12, -9, 0];
+
+        assert_eq!(
+            find_indices(&expense_report_entries, 2, 7),
+            Some(vec![0, 3])
+        );
+        assert_eq!(
+            find_indices(&expense_report_entries, 3, 5),
+            Some(vec![0, 1, 2])
+        );
+        for combination_size in 1..=6 {
+            for target in -20..=30 {
+                assert_agrees_with_brute_force(&expense_report_entries, combination_size, target);
+            }
+        }
+    }
+
+    #[test]
+    fn widens_entries_near_the_limits() {
+        assert_eq!(
+            find_indices(&[i64::MAX, 1, -1], 2, i64::MAX - 1),
+            Some(vec![0, 2])
+        );
+        assert_eq!(find_indices(&[i64::MIN, 5], 2, i64::MAX), None);
+        assert_eq!(
+            find_indices(&[i64::MAX, i64::MAX, i64::MIN], 3, i64::MAX - 1),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(
+            find_indices(&[i64::MAX, i64::MIN, i64::MAX, i64::MIN], 4, -2),
+            Some(vec![0, 1, 2, 3])
+        );
+        assert_eq!(find_indices(&[i64::MAX, i64::MAX, 1, 2], 4, -1), None);
+    }
+}
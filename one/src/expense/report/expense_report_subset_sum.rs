@@ -0,0 +1,249 @@
+use super::ExpenseReportEntry;
+use anyhow::{Error, Result};
+use std::convert::TryFrom;
+use std::mem::size_of;
+
+/// Finds a subset of distinct expense report entries, of any size, that sums
+/// to `target`.
+///
+/// Uses a pseudo-polynomial dynamic programming table with one cell for every
+/// sum that the entries could possibly reach, which is to say every sum from
+/// the total of the negative entries to the total of the positive entries.
+/// Each cell remembers the first entry that made its sum reachable, so that a
+/// witness subset can be traced back from `target`.
+///
+/// Returns `Ok(None)` if no subset sums to `target`, and `Err(...)` if
+/// `target` lies outside of the range of sums covered by the table, or if the
+/// table would take up more than `memory_limit_in_bytes`.
+pub fn find_expense_report_entry_subset(
+    expense_report_entries: &[i64],
+    target: i64,
+    memory_limit_in_bytes: usize,
+) -> Result<Option<Vec<ExpenseReportEntry>>> {
+    let min_sum = expense_report_entries
+        .iter()
+        .filter(|expense_report_entry| **expense_report_entry < 0)
+        .map(|expense_report_entry| *expense_report_entry as i128)
+        .sum::<i128>();
+    let max_sum = expense_report_entries
+        .iter()
+        .filter(|expense_report_entry| **expense_report_entry > 0)
+        .map(|expense_report_entry| *expense_report_entry as i128)
+        .sum::<i128>();
+
+    if (target as i128) < min_sum || (target as i128) > max_sum {
+        return Err(Error::msg(format!(
+            "Target {} lies outside of the range of sums that the expense report entries can reach ([{}, {}])",
+            target, min_sum, max_sum
+        )));
+    }
+
+    let number_of_sums = usize::try_from(max_sum - min_sum + 1).unwrap_or(usize::MAX);
+    let table_size_in_bytes = number_of_sums.saturating_mul(size_of::<Option<usize>>());
+    if table_size_in_bytes > memory_limit_in_bytes {
+        return Err(Error::msg(format!(
+            "Subset sum table for {} sums would take up {} bytes, exceeding the limit of {} bytes",
+            number_of_sums, table_size_in_bytes, memory_limit_in_bytes
+        )));
+    }
+
+    let sum_position = |sum: i128| (sum - min_sum) as usize;
+    let target_position = sum_position(target as i128);
+    let empty_sum_position = sum_position(0);
+
+    // Each cell holds the index of the first entry that made its sum
+    // reachable, and the empty subset makes `0` reachable from the get-go.
+    let mut reached_by: Vec<Option<usize>> = vec![None; number_of_sums];
+    let is_reachable = |reached_by: &[Option<usize>], position: usize| {
+        position == empty_sum_position || reached_by[position].is_some()
+    };
+
+    for (index, expense_report_entry) in expense_report_entries.iter().enumerate() {
+        if is_reachable(&reached_by, target_position) {
+            break;
+        }
+
+        let value = *expense_report_entry as i128;
+        if value == 0 {
+            continue;
+        }
+
+        // Sums are visited moving away from `value`'s direction, so that each
+        // newly reached sum is not built upon again using the same entry.
+        let positions: Box<dyn Iterator<Item = usize>> = if value > 0 {
+            Box::new((0..number_of_sums).rev())
+        } else {
+            Box::new(0..number_of_sums)
+        };
+
+        for position in positions {
+            if is_reachable(&reached_by, position) {
+                continue;
+            }
+
+            let previous_position = position as i128 - value;
+            if previous_position >= 0
+                && (previous_position as usize) < number_of_sums
+                && is_reachable(&reached_by, previous_position as usize)
+            {
+                reached_by[position] = Some(index);
+            }
+        }
+    }
+
+    if !is_reachable(&reached_by, target_position) {
+        return Ok(None);
+    }
+
+    // Entries are traced back in descending order of index, since every sum
+    // was first reached from a sum built out of earlier entries only.
+    let mut subset = vec![];
+    let mut position = target_position;
+    while let Some(index) = reached_by[position] {
+        subset.push(ExpenseReportEntry {
+            index,
+            value: expense_report_entries[index],
+        });
+
+        position = (position as i128 - expense_report_entries[index] as i128) as usize;
+    }
+    subset.reverse();
+
+    Ok(Some(subset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Generous enough for every table built by these tests.
+    const MEMORY_LIMIT_IN_BYTES: usize = 1 << 20;
+
+    /// Asserts that `subset` is made up of distinct `expense_report_entries`,
+    /// in order of index, that sum to `target`.
+    fn assert_witness(expense_report_entries: &[i64], subset: &[ExpenseReportEntry], target: i64) {
+        assert!(subset
+            .windows(2)
+            .all(|window| window[0].index < window[1].index));
+        assert!(subset.iter().all(|expense_report_entry| {
+            expense_report_entries[expense_report_entry.index] == expense_report_entry.value
+        }));
+        assert_eq!(
+            subset
+                .iter()
+                .map(|expense_report_entry| expense_report_entry.value)
+                .sum::<i64>(),
+            target
+        );
+    }
+
+    /// Returns `true` if some subset of `expense_report_entries` sums to
+    /// `target`, found by checking every subset one by one.
+    fn is_reachable_by_brute_force(expense_report_entries: &[i64], target: i64) -> bool {
+        (0..(1u32 << expense_report_entries.len())).any(|mask| {
+            expense_report_entries
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| mask & (1 << index) != 0)
+                .map(|(_, expense_report_entry)| *expense_report_entry)
+                .sum::<i64>()
+                == target
+        })
+    }
+
+    #[test]
+    fn finds_witness_of_distinct_entries() {
+        let expense_report_entries = [1721, 979, 366, 299, 675, 1456];
+
+        let subset =
+            find_expense_report_entry_subset(&expense_report_entries, 2020, MEMORY_LIMIT_IN_BYTES)
+                .unwrap()
+                .unwrap();
+
+        assert_witness(&expense_report_entries, &subset, 2020);
+    }
+
+    #[test]
+    fn uses_repeated_values_once_each() {
+        let expense_report_entries = [1010, 1200];
+
+        assert_eq!(
+            find_expense_report_entry_subset(&expense_report_entries, 2020, MEMORY_LIMIT_IN_BYTES)
+                .unwrap(),
+            None
+        );
+
+        let expense_report_entries = [1010, 7, 1010];
+        let subset =
+            find_expense_report_entry_subset(&expense_report_entries, 2020, MEMORY_LIMIT_IN_BYTES)
+                .unwrap()
+                .unwrap();
+
+        assert_witness(&expense_report_entries, &subset, 2020);
+    }
+
+    #[test]
+    fn finds_nothing_when_no_subset_sums_to_target() {
+        assert_eq!(
+            find_expense_report_entry_subset(&[2, 4, 6], 5, MEMORY_LIMIT_IN_BYTES).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_target_out_of_range() {
+        assert!(find_expense_report_entry_subset(&[2, 4, 6], 13, MEMORY_LIMIT_IN_BYTES).is_err());
+        assert!(find_expense_report_entry_subset(&[2, 4, 6], -1, MEMORY_LIMIT_IN_BYTES).is_err());
+        assert!(find_expense_report_entry_subset(&[-2, 4], -3, MEMORY_LIMIT_IN_BYTES).is_err());
+    }
+
+    #[test]
+    fn rejects_table_exceeding_memory_limit() {
+        let table_size_in_bytes = 13 * size_of::<Option<usize>>();
+
+        assert!(find_expense_report_entry_subset(&[2, 4, 6], 6, table_size_in_bytes).is_ok());
+        assert!(find_expense_report_entry_subset(&[2, 4, 6], 6, table_size_in_bytes - 1).is_err());
+        assert!(
+            find_expense_report_entry_subset(&[i64::MAX, i64::MIN], 0, MEMORY_LIMIT_IN_BYTES)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn finds_empty_subset_for_zero() {
+        assert_eq!(
+            find_expense_report_entry_subset(&[2, 4, 6], 0, MEMORY_LIMIT_IN_BYTES).unwrap(),
+            Some(vec![])
+        );
+        assert_eq!(
+            find_expense_report_entry_subset(&[-3, 3], 0, MEMORY_LIMIT_IN_BYTES).unwrap(),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn finds_subsets_of_mixed_sign_entries() {
+        let expense_report_entries = [5, -3, 8, -2, 0, 11, -7];
+        let min_sum = -12;
+        let max_sum = 24;
+
+        for target in min_sum..=max_sum {
+            let subset = find_expense_report_entry_subset(
+                &expense_report_entries,
+                target,
+                MEMORY_LIMIT_IN_BYTES,
+            )
+            .unwrap();
+
+            assert_eq!(
+                subset.is_some(),
+                is_reachable_by_brute_force(&expense_report_entries, target),
+                "target {}",
+                target
+            );
+            if let Some(subset) = subset {
+                assert_witness(&expense_report_entries, &subset, target);
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+/// Iterates through every ascending sequence of `size` indices below `length`,
+/// in lexicographic order.
+pub struct IndexCombinations {
+    /// Sequence to yield next, or `None` once every sequence has been yielded.
+    next_indices: Option<Vec<usize>>,
+    /// Every index is less than this.
+    length: usize,
+}
+
+impl IndexCombinations {
+    /// Creates a new `IndexCombinations`.
+    pub fn new(length: usize, size: usize) -> IndexCombinations {
+        IndexCombinations {
+            next_indices: if size <= length {
+                Some((0..size).collect())
+            } else {
+                None
+            },
+            length,
+        }
+    }
+}
+
+impl Iterator for IndexCombinations {
+    type Item = Vec<usize>;
+
+    // Advances to the next sequence of indices.
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let indices = self.next_indices.take()?;
+        let size = indices.len();
+
+        // The rightmost index that has not yet reached its largest value is
+        // bumped, and every index after it is reset to follow on directly.
+        let bumpable_position = (0..size)
+            .rev()
+            .find(|position| indices[*position] < self.length - size + position);
+
+        if let Some(bumpable_position) = bumpable_position {
+            let mut next_indices = indices.clone();
+
+            next_indices[bumpable_position] += 1;
+            for position in (bumpable_position + 1)..size {
+                next_indices[position] = next_indices[position - 1] + 1;
+            }
+
+            self.next_indices = Some(next_indices);
+        }
+
+        Some(indices)
+    }
+}
@@ -0,0 +1,17 @@
+mod expense_report_entry;
+mod expense_report_entry_combinations;
+mod expense_report_search;
+mod expense_report_subset_sum;
+mod index_combinations;
+
+/// A single expense report entry, along with where it appeared in the report.
+pub type ExpenseReportEntry = expense_report_entry::ExpenseReportEntry;
+
+/// Lazily iterates through every combination of distinct expense report
+/// entries that sums to a target.
+pub type ExpenseReportEntryCombinations<'a> =
+    expense_report_entry_combinations::ExpenseReportEntryCombinations<'a>;
+
+pub use expense_report_entry_combinations::count_expense_report_entry_combinations;
+pub use expense_report_search::find_expense_report_entry_combination;
+pub use expense_report_subset_sum::find_expense_report_entry_subset;
@@ -1,88 +1,133 @@
 extern crate anyhow;
 extern crate tokio;
 
+mod expense;
+
 use anyhow::{Context, Error, Result};
+use expense::report::{
+    count_expense_report_entry_combinations, find_expense_report_entry_combination,
+    find_expense_report_entry_subset, ExpenseReportEntry, ExpenseReportEntryCombinations,
+};
 use std::env::current_dir;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
+/// Most memory that the subset sum search is allowed to use for its table.
+const EXPENSE_REPORT_SUBSET_SUM_MEMORY_LIMIT_IN_BYTES: usize = 64 * 1024 * 1024;
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let expense_report_entries = read_expense_report_entries()
         .await
         .context("Failed to read expense report")?;
 
-    let expense_report_entry_pair = find_expense_report_entry_pair(&expense_report_entries)
-        .context("Could not find expense report entry pair")?;
+    for combination_size in 2..=3 {
+        let expense_report_entry_combination = find_expense_report_entry_combination(
+            &expense_report_entries,
+            combination_size,
+            /* target= */ 2020,
+        )
+        .with_context(|| {
+            format!(
+                "Could not find a combination of {} expense report entries",
+                combination_size
+            )
+        })?;
 
-    println!(
-        "{} × {} = {}",
-        expense_report_entry_pair.0,
-        expense_report_entry_pair.1,
-        expense_report_entry_pair.0 * expense_report_entry_pair.1
-    );
+        println!(
+            "{}",
+            describe_expense_report_entry_combination(&expense_report_entry_combination)
+                .context("Failed to multiply expense report entries")?
+        );
 
-    let expense_report_entry_trio = find_expense_report_entry_trio(&expense_report_entries)
-        .context("Could not find expense report entry trio")?;
+        let number_of_expense_report_entry_combinations = count_expense_report_entry_combinations(
+            &expense_report_entries,
+            combination_size,
+            /* target= */ 2020,
+        );
+
+        if number_of_expense_report_entry_combinations > 1 {
+            println!(
+                "...along with {} other combinations:",
+                number_of_expense_report_entry_combinations - 1
+            );
+
+            let other_expense_report_entry_combinations = ExpenseReportEntryCombinations::new(
+                &expense_report_entries,
+                combination_size,
+                /* target= */ 2020,
+            )
+            .filter(|other_expense_report_entry_combination| {
+                *other_expense_report_entry_combination != expense_report_entry_combination
+            });
+
+            for other_expense_report_entry_combination in other_expense_report_entry_combinations {
+                println!(
+                    "\t{}",
+                    describe_expense_report_entry_combination(
+                        &other_expense_report_entry_combination
+                    )
+                    .context("Failed to multiply expense report entries")?
+                );
+            }
+        }
+    }
+
+    let expense_report_entry_subset = find_expense_report_entry_subset(
+        &expense_report_entries,
+        /* target= */ 2020,
+        EXPENSE_REPORT_SUBSET_SUM_MEMORY_LIMIT_IN_BYTES,
+    )
+    .context("Failed to search for an expense report entry subset")?
+    .context("Could not find an expense report entry subset")?;
 
     println!(
-        "{} × {} × {} = {}",
-        expense_report_entry_trio.0,
-        expense_report_entry_trio.1,
-        expense_report_entry_trio.2,
-        expense_report_entry_trio.0 * expense_report_entry_trio.1 * expense_report_entry_trio.2
+        "\nSubset of {} expense report entries summing to 2020: {}",
+        expense_report_entry_subset.len(),
+        expense_report_entry_subset
+            .iter()
+            .map(|expense_report_entry| expense_report_entry.to_string())
+            .collect::<Vec<String>>()
+            .join(" + ")
     );
 
     Ok(())
 }
 
-/// Finds a pair of expense report enties that sum to `2020`.
-fn find_expense_report_entry_pair(expense_report_entries: &Vec<i32>) -> Option<(i32, i32)> {
-    expense_report_entries
-        .iter()
-        .flat_map(|expense_report_entry| {
-            expense_report_entries
-                .iter()
-                .map(move |other_expense_report_entry| {
-                    (expense_report_entry, other_expense_report_entry)
+/// Describes the `expense_report_entry_combination` as the product of its
+/// entries.
+///
+/// Returns `Err(...)` if the product does not fit in a 64-bit integer.
+fn describe_expense_report_entry_combination(
+    expense_report_entry_combination: &[ExpenseReportEntry],
+) -> Result<String> {
+    let product = expense_report_entry_combination.iter().try_fold(
+        1i64,
+        |product, expense_report_entry| {
+            product
+                .checked_mul(expense_report_entry.value)
+                .with_context(|| {
+                    format!(
+                        "Multiplying {} by expense report entry {} overflows",
+                        product, expense_report_entry
+                    )
                 })
-        })
-        .find(|(expense_report_entry, other_expense_report_entry)| {
-            *expense_report_entry + *other_expense_report_entry == 2020
-        })
-        .map(|(a, b)| (*a, *b))
-}
+        },
+    )?;
 
-/// Finds a trio of expense report enties that sum to `2020`.
-fn find_expense_report_entry_trio(expense_report_entries: &Vec<i32>) -> Option<(i32, i32, i32)> {
-    expense_report_entries
-        .iter()
-        .flat_map(|expense_report_entry| {
-            expense_report_entries
-                .iter()
-                .flat_map(move |other_expense_report_entry| {
-                    expense_report_entries
-                        .iter()
-                        .map(move |another_expense_report_entry| {
-                            (
-                                expense_report_entry,
-                                other_expense_report_entry,
-                                another_expense_report_entry,
-                            )
-                        })
-                })
-        })
-        .find(
-            |(expense_report_entry, other_expense_report_entry, another_expense_report_entry)| {
-                *expense_report_entry + *other_expense_report_entry + *another_expense_report_entry
-                    == 2020
-            },
-        )
-        .map(|(a, b, c)| (*a, *b, *c))
+    Ok(format!(
+        "{} = {}",
+        expense_report_entry_combination
+            .iter()
+            .map(|expense_report_entry| expense_report_entry.to_string())
+            .collect::<Vec<String>>()
+            .join(" × "),
+        product
+    ))
 }
 
-/// Reads the input file, returning each line represented as a 32-bit integer.
-async fn read_expense_report_entries() -> Result<Vec<i32>, Error> {
+/// Reads the input file, returning each line represented as a 64-bit integer.
+async fn read_expense_report_entries() -> Result<Vec<i64>, Error> {
     let pwd = current_dir().context("Failed to read current working directory")?;
     let expense_report_file_path = pwd.join("files/input.txt");
 
@@ -113,7 +158,7 @@ async fn read_expense_report_entries() -> Result<Vec<i32>, Error> {
         .map(
             |(expense_report_file_line_index, expense_report_file_line_text)| {
                 expense_report_file_line_text
-                    .parse::<i32>()
+                    .parse::<i64>()
                     .with_context(|| {
                         format!(
                     "Failed to convert expense report file entry at line {} (\"{}\") to integer",
@@ -1,20 +1,165 @@
 extern crate anyhow;
+extern crate rand;
 
 mod slope;
 mod toboggan;
 
 use anyhow::{Context, Error, Result};
-use slope::atlas::{SlopeAtlas, SlopeFeature};
-use std::env::current_dir;
-use toboggan::trajectory::TobogganTrajectory;
+use slope::atlas::{
+    SlopeAtlas, SlopeAtlasGenerator, SlopeEdgeMode, SlopeFeature, SlopeFeatureCosts,
+};
+use std::env::{args, current_dir};
+use std::ops::RangeInclusive;
+use std::time::Instant;
+use toboggan::path::{TobogganPath, TobogganPathPlanner};
+use toboggan::simulation::{TobogganCollision, TobogganSimulation};
+use toboggan::trajectory::{
+    DynamicTobogganTrajectory, TobogganPhysics, TobogganTrajectory, TobogganTrajectorySearch,
+    TobogganTrajectoryTreeCounter,
+};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
+/// Right speeds of the trajectories searched by `--search`.
+const SEARCHED_X_VELOCITIES: RangeInclusive<i64> = 0..=10;
+
+/// Down speeds of the trajectories searched by `--search`.
+const SEARCHED_Y_VELOCITIES: RangeInclusive<i64> = 1..=3;
+
+/// Right speeds of the trajectories timed by `--benchmark`, many more than
+/// there are features in a contour.
+const BENCHMARKED_X_VELOCITIES: RangeInclusive<i64> = -100..=100;
+
+/// Horizontal distances that steerable toboggans can move per contour, as
+/// planned by `--plan`.
+const PLANNED_LANE_CHANGES: [i64; 3] = [-1, 0, 1];
+
+/// Calculates how many trees on a slope atlas fall along a trajectory.
+type TreeCountingMethod = fn(&SlopeAtlas, &TobogganTrajectory) -> usize;
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let slope_atlas = read_slope_atlas()
+    let is_rendered = args().any(|argument| argument == "--render");
+    let number_of_searched_trajectories_shown =
+        match args().find_map(|argument| argument.strip_prefix("--search=").map(str::to_owned)) {
+            Some(number_of_trajectories) => {
+                Some(number_of_trajectories.parse::<usize>().with_context(|| {
+                    format!(
+                        "Failed to parse number of trajectories \"{}\"",
+                        number_of_trajectories
+                    )
+                })?)
+            }
+            None => None,
+        };
+    let planned_starting_x_position =
+        match args().find_map(|argument| argument.strip_prefix("--plan=").map(str::to_owned)) {
+            Some(starting_x_position) => {
+                Some(starting_x_position.parse::<i64>().with_context(|| {
+                    format!(
+                        "Failed to parse starting x-position \"{}\"",
+                        starting_x_position
+                    )
+                })?)
+            }
+            None => None,
+        };
+    let number_of_benchmarked_contours = match args()
+        .find_map(|argument| argument.strip_prefix("--benchmark=").map(str::to_owned))
+    {
+        Some(number_of_contours) => {
+            Some(number_of_contours.parse::<usize>().with_context(|| {
+                format!(
+                    "Failed to parse number of contours \"{}\"",
+                    number_of_contours
+                )
+            })?)
+        }
+        None => None,
+    };
+    let mut feature_costs = SlopeFeatureCosts::default();
+    for feature_cost_text in
+        args().filter_map(|argument| argument.strip_prefix("--cost=").map(str::to_owned))
+    {
+        let (feature_name, cost_text) = feature_cost_text.split_once(':').with_context(|| {
+            format!(
+                "Feature cost \"{}\" is not of the form `feature:cost`",
+                feature_cost_text
+            )
+        })?;
+        let cost = cost_text
+            .parse::<f64>()
+            .with_context(|| format!("Failed to parse cost \"{}\"", cost_text))?;
+
+        feature_costs.set_cost_of(SlopeFeature::from_name(feature_name)?, cost);
+    }
+    let physics =
+        match args().find_map(|argument| argument.strip_prefix("--physics=").map(str::to_owned)) {
+            Some(physics_text) => {
+                let (gravity_text, friction_text) =
+                    physics_text.split_once(',').with_context(|| {
+                        format!(
+                            "Physics \"{}\" are not of the form `gravity,friction`",
+                            physics_text
+                        )
+                    })?;
+
+                Some(TobogganPhysics::new(
+                    gravity_text
+                        .parse::<f64>()
+                        .with_context(|| format!("Failed to parse gravity \"{}\"", gravity_text))?,
+                    friction_text.parse::<f64>().with_context(|| {
+                        format!("Failed to parse friction \"{}\"", friction_text)
+                    })?,
+                )?)
+            }
+            None => None,
+        };
+    let ticks_between_departures =
+        match args().find_map(|argument| argument.strip_prefix("--simulate=").map(str::to_owned)) {
+            Some(ticks_between_departures) => {
+                Some(ticks_between_departures.parse::<u64>().with_context(|| {
+                    format!(
+                        "Failed to parse ticks between departures \"{}\"",
+                        ticks_between_departures
+                    )
+                })?)
+            }
+            None => None,
+        };
+    let edge_mode = match args()
+        .find_map(|argument| argument.strip_prefix("--edge-mode=").map(str::to_owned))
+    {
+        Some(edge_mode_name) => SlopeEdgeMode::from_name(&edge_mode_name)?,
+        None => SlopeEdgeMode::default(),
+    };
+
+    if let Some(generated_dimensions) =
+        args().find_map(|argument| argument.strip_prefix("--generate=").map(str::to_owned))
+    {
+        let seed =
+            match args().find_map(|argument| argument.strip_prefix("--seed=").map(str::to_owned)) {
+                Some(seed) => seed
+                    .parse::<u64>()
+                    .with_context(|| format!("Failed to parse seed \"{}\"", seed))?,
+                None => rand::random::<u64>(),
+            };
+        let clear_velocity = match args().find_map(|argument| {
+            argument
+                .strip_prefix("--clear-velocity=")
+                .map(str::to_owned)
+        }) {
+            Some(clear_velocity) => Some(parse_velocity(&clear_velocity)?),
+            None => None,
+        };
+
+        return print_generated_slope_atlas(&generated_dimensions, clear_velocity, seed);
+    }
+
+    let mut slope_atlas = read_slope_atlas()
         .await
         .context("Failed to read slope atlas")?;
+    slope_atlas.edge_mode = edge_mode;
 
     let trajectories: Vec<TobogganTrajectory> = vec![
         TobogganTrajectory::new((0, 0), (1, 1)),
@@ -35,7 +180,16 @@ async fn main() -> Result<()> {
         .collect::<Vec<(&TobogganTrajectory, usize)>>();
 
     for (trajectory, tree_count) in trajectory_tree_counts.iter() {
-        println!("{}: {}", trajectory, tree_count);
+        println!(
+            "{}: {} (weighted score {})",
+            trajectory,
+            tree_count,
+            weighted_score_along_trajectory(&slope_atlas, trajectory, &feature_costs)
+        );
+
+        if is_rendered {
+            println!("{}", slope_atlas.render(trajectory.descend(&slope_atlas)));
+        }
     }
 
     let tree_count_product = trajectory_tree_counts
@@ -45,6 +199,129 @@ async fn main() -> Result<()> {
 
     println!("\nTree count product: {}", tree_count_product);
 
+    if let Some(number_of_searched_trajectories_shown) = number_of_searched_trajectories_shown {
+        let trajectory_search = TobogganTrajectorySearch::new(
+            &slope_atlas,
+            (0, 0),
+            SEARCHED_X_VELOCITIES,
+            SEARCHED_Y_VELOCITIES,
+        );
+
+        if let Some((trajectory, tree_count)) = trajectory_search.best() {
+            println!("\nFewest trees: {}: {}", trajectory, tree_count);
+        }
+
+        println!(
+            "\nTop {} trajectories:",
+            number_of_searched_trajectories_shown
+        );
+        for (trajectory, tree_count) in trajectory_search.top(number_of_searched_trajectories_shown)
+        {
+            println!("\t{}: {}", trajectory, tree_count);
+        }
+    }
+
+    if let Some(planned_starting_x_position) = planned_starting_x_position {
+        let path_planner = TobogganPathPlanner::new(PLANNED_LANE_CHANGES.to_vec());
+
+        let path: Option<TobogganPath> =
+            path_planner.plan(&slope_atlas, planned_starting_x_position);
+        match path {
+            Some(path) => {
+                println!(
+                    "\nFewest trees along a steered path from x-position {}: {}",
+                    planned_starting_x_position, path.tree_count
+                );
+
+                if is_rendered {
+                    println!("{}", slope_atlas.render(path.positions.iter().copied()));
+                }
+            }
+            None => println!(
+                "\nThere is no steered path from x-position {}",
+                planned_starting_x_position
+            ),
+        }
+    }
+
+    if let Some(physics) = physics {
+        println!();
+
+        for trajectory in trajectories.iter() {
+            let (x_velocity, y_velocity) = trajectory.velocity();
+            let dynamic_trajectory = DynamicTobogganTrajectory::new(
+                (0, 0),
+                (x_velocity as f64, y_velocity as f64),
+                physics.clone(),
+            );
+
+            let ticks = dynamic_trajectory
+                .descend(&slope_atlas)
+                .collect::<Vec<((i64, i64), (f64, f64))>>();
+            let mut landed_positions = ticks
+                .iter()
+                .map(|(position, _velocity)| *position)
+                .collect::<Vec<(i64, i64)>>();
+            landed_positions.dedup();
+
+            let tree_count = landed_positions
+                .iter()
+                .filter(|position| slope_atlas.feature_at(**position) == Some(SlopeFeature::Tree))
+                .count();
+            let (final_x_velocity, final_y_velocity) = ticks.last().map_or(
+                (x_velocity as f64, y_velocity as f64),
+                |(_position, velocity)| *velocity,
+            );
+
+            println!(
+                "{}: {} over {} ticks, ending at velocity ({:.2}, {:.2})",
+                dynamic_trajectory,
+                tree_count,
+                ticks.len(),
+                final_x_velocity,
+                final_y_velocity
+            );
+
+            if is_rendered {
+                println!("{}", slope_atlas.render(landed_positions));
+            }
+        }
+    }
+
+    if let Some(ticks_between_departures) = ticks_between_departures {
+        let mut simulation = TobogganSimulation::new(&slope_atlas);
+        for (rider, trajectory) in trajectories.iter().enumerate() {
+            simulation.add_rider(*trajectory, rider as u64 * ticks_between_departures)?;
+        }
+        simulation.run();
+        let collisions: &[TobogganCollision] = simulation.collisions();
+
+        println!(
+            "\nCollisions over {} ticks, with riders departing {} ticks apart: {}",
+            simulation.number_of_ticks(),
+            ticks_between_departures,
+            collisions.len()
+        );
+        for collision in collisions {
+            println!(
+                "\ttick {}: riders {:?} at {:?}",
+                collision.tick, collision.riders, collision.position
+            );
+        }
+
+        println!("\nTrees per rider:");
+        for (rider, tree_count) in simulation.tree_counts().iter().enumerate() {
+            println!(
+                "\trider {} ({}): {}",
+                rider, trajectories[rider], tree_count
+            );
+        }
+    }
+
+    if let Some(number_of_benchmarked_contours) = number_of_benchmarked_contours {
+        benchmark_tree_counting(&slope_atlas, &trajectories, number_of_benchmarked_contours)?;
+    }
+
     Ok(())
 }
 
@@ -55,7 +332,7 @@ fn number_of_trees_along_trajectory(
     trajectory: &TobogganTrajectory,
 ) -> usize {
     trajectory
-        .descend(slope_atlas.height)
+        .descend(slope_atlas)
         .map(|position| {
             slope_atlas
                 .feature_at(position)
@@ -65,6 +342,247 @@ fn number_of_trees_along_trajectory(
         .count()
 }
 
+/// Adds up the cost, as weighed by `feature_costs`, of every feature on the
+/// `slope_atlas` that falls along the given `trajectory`.
+fn weighted_score_along_trajectory(
+    slope_atlas: &SlopeAtlas,
+    trajectory: &TobogganTrajectory,
+    feature_costs: &SlopeFeatureCosts,
+) -> f64 {
+    trajectory
+        .descend(slope_atlas)
+        .filter_map(|position| slope_atlas.feature_at(position))
+        .map(|feature| feature_costs.cost_of(feature))
+        .sum()
+}
+
+/// Calculates how many trees on the `slope_atlas` fall along the given
+/// `trajectory`, reading the trees of each contour a word at a time.
+///
+/// Only works for atlases constructed with `SlopeAtlas::from_file_packed(...)`.
+fn number_of_trees_along_trajectory_by_word(
+    slope_atlas: &SlopeAtlas,
+    trajectory: &TobogganTrajectory,
+) -> usize {
+    trajectory
+        .descend(slope_atlas)
+        .filter(|(x_position, y_position)| {
+            match (
+                slope_atlas
+                    .edge_mode
+                    .align(*x_position, slope_atlas.breadth),
+                slope_atlas.tree_words(*y_position),
+            ) {
+                (Some((feature_index, _is_mirrored)), Some(tree_words)) => {
+                    (tree_words[(feature_index / 64) as usize] >> (feature_index % 64)) & 1 == 1
+                }
+                _ => false,
+            }
+        })
+        .count()
+}
+
+/// Times counting the trees along each of the `trajectories` down a slope
+/// `number_of_contours` tall, made by repeating the contours of the
+/// `slope_atlas`, with each way of laying out its contours.
+///
+/// Returns `Err(...)` if the tree counts do not all agree.
+fn benchmark_tree_counting(
+    slope_atlas: &SlopeAtlas,
+    trajectories: &[TobogganTrajectory],
+    number_of_contours: usize,
+) -> Result<()> {
+    let slope_atlas_file_contents = slope_atlas
+        .to_string()
+        .lines()
+        .cycle()
+        .take(number_of_contours)
+        .map(|line| format!("{}\n", line))
+        .collect::<String>();
+
+    let mut unpacked_slope_atlas = SlopeAtlas::from_file(&slope_atlas_file_contents)?;
+    unpacked_slope_atlas.edge_mode = slope_atlas.edge_mode;
+    let mut packed_slope_atlas = SlopeAtlas::from_file_packed(&slope_atlas_file_contents)?;
+    packed_slope_atlas.edge_mode = slope_atlas.edge_mode;
+
+    let tree_counting_methods: [(&str, &SlopeAtlas, TreeCountingMethod); 3] = [
+        (
+            "unpacked",
+            &unpacked_slope_atlas,
+            number_of_trees_along_trajectory,
+        ),
+        (
+            "packed",
+            &packed_slope_atlas,
+            number_of_trees_along_trajectory,
+        ),
+        (
+            "packed, by word",
+            &packed_slope_atlas,
+            number_of_trees_along_trajectory_by_word,
+        ),
+    ];
+
+    println!(
+        "\nCounting trees along {} trajectories down {} contours:",
+        trajectories.len(),
+        number_of_contours
+    );
+
+    let mut expected_tree_counts = None;
+    for (tree_counting_method_name, benchmarked_slope_atlas, number_of_trees) in
+        tree_counting_methods.iter()
+    {
+        let started_at = Instant::now();
+        let tree_counts = trajectories
+            .iter()
+            .map(|trajectory| number_of_trees(benchmarked_slope_atlas, trajectory))
+            .collect::<Vec<usize>>();
+        let elapsed = started_at.elapsed();
+
+        println!(
+            "\t{:<16} {:>10.3?} {:?}",
+            format!("{}:", tree_counting_method_name),
+            elapsed,
+            tree_counts
+        );
+
+        match &expected_tree_counts {
+            None => expected_tree_counts = Some(tree_counts),
+            Some(expected_tree_counts) if *expected_tree_counts != tree_counts => {
+                return Err(Error::msg(format!(
+                    "Tree counts of the {} method disagree with {:?}",
+                    tree_counting_method_name, expected_tree_counts
+                )))
+            }
+            Some(_) => {}
+        }
+    }
+
+    benchmark_periodic_tree_counting(&unpacked_slope_atlas)
+}
+
+/// Times counting the trees along every trajectory with one of the
+/// `BENCHMARKED_X_VELOCITIES` and `SEARCHED_Y_VELOCITIES` down the
+/// `slope_atlas`, one trajectory at a time and all at once with a
+/// `TobogganTrajectoryTreeCounter`.
+///
+/// Returns `Err(...)` if the tree counts do not agree.
+fn benchmark_periodic_tree_counting(slope_atlas: &SlopeAtlas) -> Result<()> {
+    let velocities = BENCHMARKED_X_VELOCITIES
+        .flat_map(|x_velocity| {
+            SEARCHED_Y_VELOCITIES.map(move |y_velocity| (x_velocity, y_velocity))
+        })
+        .collect::<Vec<(i64, i64)>>();
+
+    println!(
+        "\nCounting trees along {} trajectories down {} contours:",
+        velocities.len(),
+        slope_atlas.height
+    );
+
+    let started_at = Instant::now();
+    let descended_tree_counts = velocities
+        .iter()
+        .map(|velocity| {
+            number_of_trees_along_trajectory(
+                slope_atlas,
+                &TobogganTrajectory::new((0, 0), *velocity),
+            )
+        })
+        .collect::<Vec<usize>>();
+    println!("\t{:<16} {:>10.3?}", "descended:", started_at.elapsed());
+
+    let started_at = Instant::now();
+    let tree_counter =
+        match TobogganTrajectoryTreeCounter::new(slope_atlas, (0, 0), SEARCHED_Y_VELOCITIES) {
+            Ok(tree_counter) => tree_counter,
+            Err(error) => {
+                println!("\t{:<16} skipped: {}", "periodic:", error);
+
+                return Ok(());
+            }
+        };
+    let periodic_tree_counts = velocities
+        .iter()
+        .map(|velocity| tree_counter.number_of_trees(*velocity))
+        .collect::<Option<Vec<usize>>>()
+        .context("Some down speeds were not counted")?;
+    println!("\t{:<16} {:>10.3?}", "periodic:", started_at.elapsed());
+
+    if periodic_tree_counts != descended_tree_counts {
+        return Err(Error::msg(format!(
+            "Tree counts of the periodic method {:?} disagree with {:?}",
+            periodic_tree_counts, descended_tree_counts
+        )));
+    }
+
+    Ok(())
+}
+
+/// Prints a slope atlas file generated from `seed`, with dimensions of the form
+/// `breadth,height,tree_density`, that is left clear along the trajectory from
+/// `(0, 0)` with `clear_velocity`, if there is one.
+fn print_generated_slope_atlas(
+    generated_dimensions: &str,
+    clear_velocity: Option<(i64, i64)>,
+    seed: u64,
+) -> Result<()> {
+    let dimensions = generated_dimensions.split(',').collect::<Vec<&str>>();
+    let (breadth_text, height_text, tree_density_text) = match dimensions[..] {
+        [breadth_text, height_text, tree_density_text] => {
+            (breadth_text, height_text, tree_density_text)
+        }
+        _ => {
+            return Err(Error::msg(format!(
+                "Dimensions \"{}\" are not of the form `breadth,height,tree_density`",
+                generated_dimensions
+            )))
+        }
+    };
+
+    let mut slope_atlas_generator = SlopeAtlasGenerator::new(
+        breadth_text
+            .parse::<i64>()
+            .with_context(|| format!("Failed to parse breadth \"{}\"", breadth_text))?,
+        height_text
+            .parse::<i64>()
+            .with_context(|| format!("Failed to parse height \"{}\"", height_text))?,
+        tree_density_text
+            .parse::<f64>()
+            .with_context(|| format!("Failed to parse tree density \"{}\"", tree_density_text))?,
+    );
+    slope_atlas_generator.clear_velocity = clear_velocity;
+
+    eprintln!("Generating slope atlas with seed {}", seed);
+    let slope_atlas_file_contents = slope_atlas_generator.generate(seed)?;
+    SlopeAtlas::from_file(&slope_atlas_file_contents)
+        .context("Generated slope atlas failed to parse")?;
+
+    print!("{}", slope_atlas_file_contents);
+
+    Ok(())
+}
+
+/// Parses a velocity of the form `right,down`.
+fn parse_velocity(velocity_text: &str) -> Result<(i64, i64)> {
+    let (x_velocity_text, y_velocity_text) = velocity_text.split_once(',').with_context(|| {
+        format!(
+            "Velocity \"{}\" is not of the form `right,down`",
+            velocity_text
+        )
+    })?;
+
+    Ok((
+        x_velocity_text
+            .parse::<i64>()
+            .with_context(|| format!("Failed to parse right speed \"{}\"", x_velocity_text))?,
+        y_velocity_text
+            .parse::<i64>()
+            .with_context(|| format!("Failed to parse down speed \"{}\"", y_velocity_text))?,
+    ))
+}
+
 /// Turns input file into a new instance of `SlopeAtlas`.
 async fn read_slope_atlas() -> Result<SlopeAtlas, Error> {
     let pwd = current_dir().context("Failed to read current working directory")?;
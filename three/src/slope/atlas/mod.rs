@@ -1,9 +1,24 @@
+mod packed_slope_contour;
 mod slope_atlas;
+mod slope_atlas_generator;
 mod slope_contour;
+mod slope_edge_mode;
 mod slope_feature;
+mod slope_feature_costs;
 
 /// Maps out the spatial make up of a slope.
 pub type SlopeAtlas = slope_atlas::SlopeAtlas;
 
+/// Generates slope atlas files at random, for load testing and fixtures.
+pub type SlopeAtlasGenerator = slope_atlas_generator::SlopeAtlasGenerator;
+
 /// Enumerates every kind of thing that can appear on the slope.
 pub type SlopeFeature = slope_feature::SlopeFeature;
+
+/// Weighs how costly it is for a toboggan to land on each kind of
+/// `SlopeFeature`.
+pub type SlopeFeatureCosts = slope_feature_costs::SlopeFeatureCosts;
+
+/// Enumerates every way that a slope can behave past its left and right
+/// edges.
+pub type SlopeEdgeMode = slope_edge_mode::SlopeEdgeMode;
@@ -0,0 +1,173 @@
+use super::slope_feature::SlopeFeature;
+use anyhow::{Context, Result};
+
+/// How many features of a `PackedSlopeContour` are packed into each word.
+const FEATURES_PER_WORD: i64 = 64;
+
+/// Represents one elevation of the slope, packed into one bit per feature.
+///
+/// Takes an eighth of the space of a `SlopeContour`, which matters for slopes
+/// millions of contours tall. Only trees and bare slope are packed; the
+/// rarer kinds of features are listed separately.
+#[derive(Debug)]
+pub struct PackedSlopeContour {
+    /// How wide this contour is.
+    breadth: i64,
+    /// Bit `i % 64` of word `i / 64` is set if the feature at index `i` of
+    /// this contour is a tree. Bits past `breadth` are never set.
+    tree_words: Vec<u64>,
+    /// Index of every feature of this contour that is neither a tree nor
+    /// nothing, along with that feature, in ascending order by index.
+    other_features: Vec<(i64, SlopeFeature)>,
+}
+
+impl PackedSlopeContour {
+    /// Creates a new `PackedSlopeContour` using the text from a slope atlas
+    /// file.
+    pub fn from_text(text: &str) -> Result<PackedSlopeContour> {
+        let mut breadth = 0;
+        let mut tree_words = vec![];
+        let mut other_features = vec![];
+
+        for text_char in text.chars() {
+            let feature = SlopeFeature::from_char(text_char)
+                .context("Failed to parse packed slope contours")?;
+
+            if breadth % FEATURES_PER_WORD == 0 {
+                tree_words.push(0);
+            }
+            match feature {
+                SlopeFeature::Tree => {
+                    *tree_words.last_mut().unwrap() |= 1 << (breadth % FEATURES_PER_WORD)
+                }
+                SlopeFeature::Nothing => {}
+                _ => other_features.push((breadth, feature)),
+            }
+
+            breadth += 1;
+        }
+
+        Ok(PackedSlopeContour {
+            breadth,
+            tree_words,
+            other_features,
+        })
+    }
+
+    /// How wide this contour is.
+    pub fn breadth(&self) -> i64 {
+        self.breadth
+    }
+
+    /// Returns the feature at the specified `feature_index`.
+    pub fn feature_at(&self, feature_index: i64) -> Option<SlopeFeature> {
+        if !(0..self.breadth).contains(&feature_index) {
+            return None;
+        }
+
+        let tree_word = self.tree_words[(feature_index / FEATURES_PER_WORD) as usize];
+        if tree_word & (1 << (feature_index % FEATURES_PER_WORD)) != 0 {
+            return Some(SlopeFeature::Tree);
+        }
+
+        match self
+            .other_features
+            .binary_search_by_key(&feature_index, |(other_feature_index, _)| {
+                *other_feature_index
+            }) {
+            Ok(other_feature_position) => Some(self.other_features[other_feature_position].1),
+            Err(_) => Some(SlopeFeature::Nothing),
+        }
+    }
+
+    /// Returns the words that the trees of this contour are packed into,
+    /// where bit `i % 64` of word `i / 64` is set if the feature at index `i`
+    /// is a tree.
+    pub fn tree_words(&self) -> &[u64] {
+        &self.tree_words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slope::atlas::slope_contour::SlopeContour;
+
+    /// Builds the text of a contour `breadth` features wide that mixes every
+    /// kind of feature, trees most of all.
+    fn mixed_contour_text(breadth: usize) -> String {
+        (0..breadth)
+            .map(|feature_index| {
+                ['#', '.', '#', '*', '~', '#', '^', '|']
+                    [(feature_index * 5 + feature_index / 3) % 8]
+            })
+            .collect()
+    }
+
+    /// Asserts that no bit past `breadth` is set in `tree_words`.
+    fn assert_clear_past_breadth(tree_words: &[u64], breadth: i64) {
+        assert_eq!(
+            tree_words.len() as i64,
+            (breadth + FEATURES_PER_WORD - 1) / FEATURES_PER_WORD
+        );
+        if breadth % FEATURES_PER_WORD != 0 {
+            assert_eq!(
+                tree_words.last().unwrap() >> (breadth % FEATURES_PER_WORD),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn agrees_with_unpacked_contour() {
+        for breadth in [1, 63, 64, 65, 150, 192] {
+            let contour_text = mixed_contour_text(breadth);
+            let packed_slope_contour = PackedSlopeContour::from_text(&contour_text).unwrap();
+            let slope_contour = SlopeContour::from_text(&contour_text).unwrap();
+
+            assert_eq!(packed_slope_contour.breadth(), slope_contour.breadth());
+            for feature_index in -1..=(breadth as i64 + 1) {
+                assert_eq!(
+                    packed_slope_contour.feature_at(feature_index),
+                    slope_contour.feature_at(feature_index),
+                    "feature {} of {}",
+                    feature_index,
+                    contour_text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn packs_every_tree_and_nothing_past_breadth() {
+        for breadth in [1, 63, 64, 65, 150, 192] {
+            let contour_text = mixed_contour_text(breadth);
+            let packed_slope_contour = PackedSlopeContour::from_text(&contour_text).unwrap();
+            let tree_words = packed_slope_contour.tree_words();
+
+            assert_clear_past_breadth(tree_words, breadth as i64);
+            assert_eq!(
+                tree_words
+                    .iter()
+                    .map(|tree_word| tree_word.count_ones() as usize)
+                    .sum::<usize>(),
+                contour_text.matches('#').count()
+            );
+        }
+
+        for breadth in [70, 128] {
+            let packed_slope_contour =
+                PackedSlopeContour::from_text(&"#".repeat(breadth as usize)).unwrap();
+
+            assert_clear_past_breadth(packed_slope_contour.tree_words(), breadth);
+            assert_eq!(
+                packed_slope_contour
+                    .tree_words()
+                    .iter()
+                    .map(|tree_word| tree_word.count_ones() as i64)
+                    .sum::<i64>(),
+                breadth
+            );
+        }
+    }
+}
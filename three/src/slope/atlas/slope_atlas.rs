@@ -1,6 +1,11 @@
+use super::packed_slope_contour::PackedSlopeContour;
 use super::slope_contour::SlopeContour;
+use super::slope_edge_mode::SlopeEdgeMode;
 use super::slope_feature::SlopeFeature;
 use anyhow::{Context, Error, Result};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::iter;
 
 /// Maps out the spatial make up of a slope.
 #[derive(Debug)]
@@ -9,9 +14,11 @@ pub struct SlopeAtlas {
     pub breadth: i64,
     /// How many contours there are.
     pub height: i64,
+    /// How the slope behaves past its left and right edges.
+    pub edge_mode: SlopeEdgeMode,
 
     /// Each contour of the slope sorted in descending order by elevation.
-    contours: Vec<SlopeContour>,
+    contours: SlopeContours,
 }
 
 impl SlopeAtlas {
@@ -20,51 +27,190 @@ impl SlopeAtlas {
     ///
     /// For more on the format of `slope_atlas_file_contents`,
     /// see https://adventofcode.com/2020/day/3.
+    ///
+    /// The slope wraps around past its edges, as it does in the puzzle.
     pub fn from_file(slope_atlas_file_contents: &str) -> Result<SlopeAtlas> {
         let contours = slope_atlas_file_contents
             .lines()
-            .map(|line| SlopeContour::from_text(line))
+            .map(SlopeContour::from_text)
             .collect::<Result<Vec<SlopeContour>>>()
             .context("Failed to read contours")?;
 
+        SlopeAtlas::from_contours(SlopeContours::Unpacked(contours))
+    }
+
+    /// Constructs a new `SlopeAtlas` based on the provided
+    /// `slope_atlas_file_contents`, like `from_file(...)` does, but packs
+    /// each contour into one bit per feature.
+    ///
+    /// Packed atlases take an eighth of the space, and give access to the
+    /// trees of each contour a word at a time through `tree_words(...)`.
+    pub fn from_file_packed(slope_atlas_file_contents: &str) -> Result<SlopeAtlas> {
+        let contours = slope_atlas_file_contents
+            .lines()
+            .map(PackedSlopeContour::from_text)
+            .collect::<Result<Vec<PackedSlopeContour>>>()
+            .context("Failed to read contours")?;
+
+        SlopeAtlas::from_contours(SlopeContours::Packed(contours))
+    }
+
+    /// Constructs a new `SlopeAtlas` out of `contours`, making sure that they
+    /// all have the same breadth.
+    fn from_contours(contours: SlopeContours) -> Result<SlopeAtlas> {
         let height = contours.len() as i64;
         if height < 1 {
             return Err(Error::msg("Slope atlas file has no contours"));
         }
 
-        let breadth = contours.get(0).unwrap().breadth();
-        let contour_with_different_breadth = contours
-            .iter()
-            .enumerate()
-            .find(|(_i, contour)| contour.breadth() != breadth);
-        if contour_with_different_breadth.is_some() {
+        let breadth = contours.breadth_at(0);
+        let contour_with_different_breadth = (0..contours.len())
+            .find(|contour_index| contours.breadth_at(*contour_index) != breadth);
+        if let Some(contour_index) = contour_with_different_breadth {
             return Err(Error::msg(format!(
                 "Contour at index {} has an incosistent breadth ({})",
-                contour_with_different_breadth.unwrap().0,
-                breadth
+                contour_index, breadth
             )));
         }
 
         Ok(SlopeAtlas {
             breadth,
             contours,
+            edge_mode: SlopeEdgeMode::default(),
             height,
         })
     }
 
-    /// Returns the feature at the specified `position`.
+    /// Returns the feature at the specified `position`, as seen through this
+    /// atlas's `edge_mode`.
     pub fn feature_at(&self, position: (i64, i64)) -> Option<SlopeFeature> {
         let (x_position, y_position) = position;
+        if y_position < 0 {
+            return None;
+        }
 
-        self.contours.get(y_position as usize).and_then(|contour| {
-            let unaligned_feature_index = x_position % self.breadth;
-            let feature_index = if unaligned_feature_index < 0 {
-                self.breadth + unaligned_feature_index
-            } else {
-                unaligned_feature_index
-            };
+        let (feature_index, _is_mirrored) = self.edge_mode.align(x_position, self.breadth)?;
 
-            contour.feature_at(feature_index)
-        })
+        self.contours.feature_at(feature_index, y_position as usize)
+    }
+
+    /// Returns the words that the trees of the contour at `y_position` are
+    /// packed into, where bit `i % 64` of word `i / 64` is set if the feature
+    /// at index `i` is a tree.
+    ///
+    /// Returns `None` if there is no such contour, or if this atlas was not
+    /// constructed with `from_file_packed(...)`.
+    pub fn tree_words(&self, y_position: i64) -> Option<&[u64]> {
+        match &self.contours {
+            SlopeContours::Packed(contours) if y_position >= 0 => contours
+                .get(y_position as usize)
+                .map(PackedSlopeContour::tree_words),
+            _ => None,
+        }
+    }
+
+    /// Draws this atlas the way slope atlas files do, marking each of the
+    /// `overlaid_positions` that lands on an obstacle, like a tree, with `X`
+    /// and every other one with `O`.
+    ///
+    /// The slope is repeated horizontally, as seen through this atlas's
+    /// `edge_mode`, until every one of the `overlaid_positions` is in view.
+    /// Anything past the edge of a bounded slope is left blank.
+    pub fn render<I>(&self, overlaid_positions: I) -> String
+    where
+        I: IntoIterator<Item = (i64, i64)>,
+    {
+        let overlaid_positions = overlaid_positions
+            .into_iter()
+            .collect::<HashSet<(i64, i64)>>();
+
+        // Only whole repetitions of the slope are drawn.
+        let min_x_position = overlaid_positions
+            .iter()
+            .map(|(x_position, _y_position)| *x_position)
+            .min()
+            .unwrap_or(0)
+            .div_euclid(self.breadth)
+            .min(0)
+            * self.breadth;
+        let max_x_position = (overlaid_positions
+            .iter()
+            .map(|(x_position, _y_position)| *x_position)
+            .max()
+            .unwrap_or(0)
+            .div_euclid(self.breadth)
+            .max(0)
+            + 1)
+            * self.breadth
+            - 1;
+
+        let mut rendering = String::new();
+        for y_position in 0..self.height {
+            for x_position in min_x_position..=max_x_position {
+                let position = (x_position, y_position);
+
+                rendering.push(
+                    match (
+                        self.feature_at(position),
+                        overlaid_positions.contains(&position),
+                    ) {
+                        (Some(feature), true) if feature.is_obstacle() => 'X',
+                        (Some(_feature), true) => 'O',
+                        (Some(feature), false) => feature.to_char(),
+                        (None, _) => ' ',
+                    },
+                );
+            }
+            rendering.push('\n');
+        }
+
+        rendering
+    }
+}
+
+impl Display for SlopeAtlas {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(iter::empty()))
+    }
+}
+
+/// Enumerates every way that the contours of a `SlopeAtlas` can be laid out in
+/// memory.
+#[derive(Debug)]
+enum SlopeContours {
+    /// One `SlopeFeature` per feature.
+    Unpacked(Vec<SlopeContour>),
+    /// One bit per feature.
+    Packed(Vec<PackedSlopeContour>),
+}
+
+impl SlopeContours {
+    /// How many contours there are.
+    fn len(&self) -> usize {
+        match self {
+            SlopeContours::Unpacked(contours) => contours.len(),
+            SlopeContours::Packed(contours) => contours.len(),
+        }
+    }
+
+    /// How wide the contour at `contour_index` is.
+    fn breadth_at(&self, contour_index: usize) -> i64 {
+        match self {
+            SlopeContours::Unpacked(contours) => contours[contour_index].breadth(),
+            SlopeContours::Packed(contours) => contours[contour_index].breadth(),
+        }
+    }
+
+    /// Returns the feature at `feature_index` of the contour at
+    /// `contour_index`.
+    fn feature_at(&self, feature_index: i64, contour_index: usize) -> Option<SlopeFeature> {
+        match self {
+            SlopeContours::Unpacked(contours) => contours
+                .get(contour_index)
+                .and_then(|contour| contour.feature_at(feature_index)),
+            SlopeContours::Packed(contours) => contours
+                .get(contour_index)
+                .and_then(|contour| contour.feature_at(feature_index)),
+        }
     }
 }
@@ -0,0 +1,215 @@
+use super::slope_feature::SlopeFeature;
+use anyhow::{Error, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Generates slope atlas files at random, for load testing and fixtures.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlopeAtlasGenerator {
+    /// How wide each generated contour is.
+    pub breadth: i64,
+    /// How many contours are generated.
+    pub height: i64,
+    /// Chance of each feature being a tree, from `0` to `1`.
+    pub tree_density: f64,
+    /// `(x, y)` velocity of a trajectory from `(0, 0)` that must not hit any
+    /// trees on a wrapping slope, if any.
+    pub clear_velocity: Option<(i64, i64)>,
+}
+
+impl SlopeAtlasGenerator {
+    /// Creates a new `SlopeAtlasGenerator` of slopes `breadth` features wide
+    /// and `height` contours tall, where each feature is a tree with a chance
+    /// of `tree_density`.
+    pub fn new(breadth: i64, height: i64, tree_density: f64) -> SlopeAtlasGenerator {
+        SlopeAtlasGenerator {
+            breadth,
+            height,
+            tree_density,
+            clear_velocity: None,
+        }
+    }
+
+    /// Generates the contents of a slope atlas file, as read by
+    /// `SlopeAtlas::from_file(...)`, that is fully determined by `seed`.
+    ///
+    /// If there is a `clear_velocity`, every feature along the trajectory
+    /// from `(0, 0)` with that velocity is left clear.
+    ///
+    /// Returns `Err(...)` if the slope would be empty, if `tree_density` is
+    /// not a chance, or if `clear_velocity` never moves down the slope.
+    pub fn generate(&self, seed: u64) -> Result<String> {
+        if self.breadth < 1 || self.height < 1 {
+            return Err(Error::msg(format!(
+                "Slope atlas must be at least 1 by 1, not {} by {}",
+                self.breadth, self.height
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.tree_density) {
+            return Err(Error::msg(format!(
+                "Tree density must be between 0 and 1, not {}",
+                self.tree_density
+            )));
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut contours = (0..self.height)
+            .map(|_y_position| {
+                (0..self.breadth)
+                    .map(|_x_position| {
+                        if rng.gen_bool(self.tree_density) {
+                            SlopeFeature::Tree
+                        } else {
+                            SlopeFeature::Nothing
+                        }
+                    })
+                    .collect::<Vec<SlopeFeature>>()
+            })
+            .collect::<Vec<Vec<SlopeFeature>>>();
+
+        if let Some((x_velocity, y_velocity)) = self.clear_velocity {
+            if y_velocity < 1 {
+                return Err(Error::msg(format!(
+                    "Clear velocity must move down the slope, not by {}",
+                    y_velocity
+                )));
+            }
+
+            for (number_of_steps, y_position) in (y_velocity..self.height)
+                .step_by(y_velocity as usize)
+                .enumerate()
+            {
+                let x_position = ((number_of_steps as i64 + 1) % self.breadth * x_velocity)
+                    .rem_euclid(self.breadth);
+
+                contours[y_position as usize][x_position as usize] = SlopeFeature::Nothing;
+            }
+        }
+
+        Ok(contours
+            .iter()
+            .map(|contour| {
+                let mut line = contour
+                    .iter()
+                    .map(|feature| feature.to_char())
+                    .collect::<String>();
+                line.push('\n');
+
+                line
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slope::atlas::SlopeAtlas;
+    use crate::toboggan::trajectory::TobogganTrajectory;
+
+    /// Counts the trees along `trajectory` one position at a time.
+    fn number_of_trees_along_trajectory(
+        slope_atlas: &SlopeAtlas,
+        trajectory: &TobogganTrajectory,
+    ) -> usize {
+        trajectory
+            .descend(slope_atlas)
+            .filter(|position| slope_atlas.feature_at(*position) == Some(SlopeFeature::Tree))
+            .count()
+    }
+
+    #[test]
+    fn generates_slope_of_requested_size() {
+        for (breadth, height) in [(1, 1), (1, 7), (31, 1), (31, 323), (100, 3)] {
+            for tree_density in [0.0, 0.25, 1.0] {
+                let slope_atlas_file_contents =
+                    SlopeAtlasGenerator::new(breadth, height, tree_density)
+                        .generate(42)
+                        .unwrap();
+                let slope_atlas = SlopeAtlas::from_file(&slope_atlas_file_contents).unwrap();
+
+                assert_eq!(slope_atlas.breadth, breadth);
+                assert_eq!(slope_atlas.height, height);
+            }
+        }
+    }
+
+    #[test]
+    fn generates_same_slope_for_same_seed() {
+        let slope_atlas_generator = SlopeAtlasGenerator {
+            clear_velocity: Some((3, 1)),
+            ..SlopeAtlasGenerator::new(31, 323, 0.3)
+        };
+
+        assert_eq!(
+            slope_atlas_generator.generate(7).unwrap(),
+            slope_atlas_generator.generate(7).unwrap()
+        );
+        assert_ne!(
+            slope_atlas_generator.generate(7).unwrap(),
+            slope_atlas_generator.generate(8).unwrap()
+        );
+    }
+
+    #[test]
+    fn leaves_trajectory_with_clear_velocity_clear() {
+        for clear_velocity in [(3, 1), (0, 1), (-2, 1), (7, 3), (-11, 2), (45, 1), (1, 400)] {
+            for seed in 0..5 {
+                let slope_atlas_file_contents = SlopeAtlasGenerator {
+                    clear_velocity: Some(clear_velocity),
+                    ..SlopeAtlasGenerator::new(13, 100, 1.0)
+                }
+                .generate(seed)
+                .unwrap();
+                let slope_atlas = SlopeAtlas::from_file(&slope_atlas_file_contents).unwrap();
+                let trajectory = TobogganTrajectory::new((0, 0), clear_velocity);
+
+                assert_eq!(
+                    number_of_trees_along_trajectory(&slope_atlas, &trajectory),
+                    0,
+                    "{}",
+                    trajectory
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_tree_density_that_is_not_a_chance() {
+        for tree_density in [-0.1, 1.1, f64::NAN, f64::INFINITY] {
+            assert!(
+                SlopeAtlasGenerator::new(5, 5, tree_density)
+                    .generate(0)
+                    .is_err(),
+                "tree density {}",
+                tree_density
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_empty_slopes() {
+        for (breadth, height) in [(0, 5), (5, 0), (0, 0), (-1, 5), (5, -1)] {
+            assert!(
+                SlopeAtlasGenerator::new(breadth, height, 0.5)
+                    .generate(0)
+                    .is_err(),
+                "{} by {}",
+                breadth,
+                height
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_clear_velocity_that_does_not_move_down() {
+        for clear_velocity in [(3, 0), (0, 0), (3, -1)] {
+            assert!(SlopeAtlasGenerator {
+                clear_velocity: Some(clear_velocity),
+                ..SlopeAtlasGenerator::new(5, 5, 0.5)
+            }
+            .generate(0)
+            .is_err());
+        }
+    }
+}
@@ -13,7 +13,7 @@ impl SlopeContour {
     pub fn from_text(text: &str) -> Result<SlopeContour> {
         let features = text
             .chars()
-            .map(|text_char| SlopeFeature::from_char(text_char))
+            .map(SlopeFeature::from_char)
             .collect::<Result<Vec<SlopeFeature>>>()
             .context("Failed to parse slop contours")?;
 
@@ -27,8 +27,6 @@ impl SlopeContour {
 
     /// Returns the feature at the specified `feature_index`.
     pub fn feature_at(&self, feature_index: i64) -> Option<SlopeFeature> {
-        self.features
-            .get(feature_index as usize)
-            .map(|feature| *feature)
+        self.features.get(feature_index as usize).copied()
     }
 }
@@ -0,0 +1,114 @@
+use anyhow::{Error, Result};
+
+/// Enumerates every way that a slope can behave past its left and right
+/// edges.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum SlopeEdgeMode {
+    /// The slope repeats endlessly to the left and right.
+    #[default]
+    Wrap,
+    /// There is nothing past the edges of the slope.
+    Bounded,
+    /// The edges of the slope are walls that toboggans bounce off of, so the
+    /// slope appears mirrored past each edge.
+    Reflect,
+}
+
+impl SlopeEdgeMode {
+    /// Returns the `SlopeEdgeMode` named by `name`, either `wrap`, `bounded`
+    /// or `reflect`.
+    pub fn from_name(name: &str) -> Result<SlopeEdgeMode> {
+        match name {
+            "wrap" => Ok(SlopeEdgeMode::Wrap),
+            "bounded" => Ok(SlopeEdgeMode::Bounded),
+            "reflect" => Ok(SlopeEdgeMode::Reflect),
+            _ => Err(Error::msg(format!(
+                "Unrecognized slope edge mode \"{}\"",
+                name
+            ))),
+        }
+    }
+
+    /// Maps `x_position` onto the index of a feature within a contour that
+    /// is `breadth` features wide.
+    ///
+    /// Alongside the index, returns `true` if the slope is mirrored at
+    /// `x_position`, meaning that anything moving there has had its
+    /// horizontal direction reversed. Returns `None` if there is nothing at
+    /// `x_position`.
+    pub fn align(&self, x_position: i64, breadth: i64) -> Option<(i64, bool)> {
+        match self {
+            SlopeEdgeMode::Wrap => Some((x_position.rem_euclid(breadth), false)),
+            SlopeEdgeMode::Bounded => {
+                if (0..breadth).contains(&x_position) {
+                    Some((x_position, false))
+                } else {
+                    None
+                }
+            }
+            SlopeEdgeMode::Reflect => {
+                let unmirrored_x_position = x_position.rem_euclid(2 * breadth);
+
+                if unmirrored_x_position < breadth {
+                    Some((unmirrored_x_position, false))
+                } else {
+                    Some((2 * breadth - 1 - unmirrored_x_position, true))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Breadth of the slope that every test aligns positions onto.
+    const BREADTH: i64 = 5;
+
+    #[test]
+    fn wraps_around_past_either_edge() {
+        let edge_mode = SlopeEdgeMode::Wrap;
+
+        assert_eq!(edge_mode.align(3, BREADTH), Some((3, false)));
+        assert_eq!(edge_mode.align(-1, BREADTH), Some((4, false)));
+        assert_eq!(edge_mode.align(BREADTH, BREADTH), Some((0, false)));
+        assert_eq!(edge_mode.align(2 * BREADTH, BREADTH), Some((0, false)));
+        assert_eq!(edge_mode.align(-23, BREADTH), Some((2, false)));
+    }
+
+    #[test]
+    fn has_nothing_past_either_edge_when_bounded() {
+        let edge_mode = SlopeEdgeMode::Bounded;
+
+        assert_eq!(edge_mode.align(0, BREADTH), Some((0, false)));
+        assert_eq!(edge_mode.align(BREADTH - 1, BREADTH), Some((4, false)));
+        assert_eq!(edge_mode.align(-1, BREADTH), None);
+        assert_eq!(edge_mode.align(BREADTH, BREADTH), None);
+        assert_eq!(edge_mode.align(2 * BREADTH, BREADTH), None);
+    }
+
+    #[test]
+    fn mirrors_past_either_edge_when_reflecting() {
+        let edge_mode = SlopeEdgeMode::Reflect;
+
+        assert_eq!(edge_mode.align(3, BREADTH), Some((3, false)));
+        assert_eq!(edge_mode.align(-1, BREADTH), Some((0, true)));
+        assert_eq!(edge_mode.align(-BREADTH, BREADTH), Some((4, true)));
+        assert_eq!(edge_mode.align(BREADTH, BREADTH), Some((4, true)));
+        assert_eq!(edge_mode.align(2 * BREADTH - 1, BREADTH), Some((0, true)));
+        assert_eq!(edge_mode.align(2 * BREADTH, BREADTH), Some((0, false)));
+    }
+
+    #[test]
+    fn mirrors_jumps_over_several_bounces() {
+        let edge_mode = SlopeEdgeMode::Reflect;
+
+        // Bounces off the right, left and right edges in turn.
+        assert_eq!(edge_mode.align(27, BREADTH), Some((2, true)));
+        // Bounces off the right and left edges in turn.
+        assert_eq!(edge_mode.align(23, BREADTH), Some((3, false)));
+        // Bounces off the left, right and left edges in turn.
+        assert_eq!(edge_mode.align(-13, BREADTH), Some((2, true)));
+    }
+}
@@ -1,22 +1,86 @@
 use anyhow::{Error, Result};
 /// Enumerates every kind of thing that can appear on the slope.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum SlopeFeature {
     /// Describes a part of the slope with nothing on it.
     Nothing,
     /// Describes a tree on the slope.
     Tree,
+    /// Describes a rock on the slope.
+    Rock,
+    /// Describes a patch of ice on the slope.
+    Ice,
+    /// Describes a mogul, a bump of packed snow, on the slope.
+    Mogul,
+    /// Describes a pylon holding up a ski lift on the slope.
+    LiftPylon,
 }
 
 impl SlopeFeature {
+    /// Every kind of thing that can appear on the slope.
+    pub const ALL: [SlopeFeature; 6] = [
+        SlopeFeature::Nothing,
+        SlopeFeature::Tree,
+        SlopeFeature::Rock,
+        SlopeFeature::Ice,
+        SlopeFeature::Mogul,
+        SlopeFeature::LiftPylon,
+    ];
+
     pub fn from_char(slope_feature_char: char) -> Result<SlopeFeature> {
-        match slope_feature_char {
-            '#' => Ok(SlopeFeature::Tree),
-            '.' => Ok(SlopeFeature::Nothing),
-            _ => Err(Error::msg(format!(
-                "Found unrecognized character '{}'",
-                slope_feature_char
-            ))),
+        SlopeFeature::ALL
+            .iter()
+            .find(|feature| feature.to_char() == slope_feature_char)
+            .copied()
+            .ok_or_else(|| {
+                Error::msg(format!(
+                    "Found unrecognized character '{}'",
+                    slope_feature_char
+                ))
+            })
+    }
+
+    /// Returns the `SlopeFeature` named by `name`, like `tree` or
+    /// `lift-pylon`.
+    pub fn from_name(name: &str) -> Result<SlopeFeature> {
+        SlopeFeature::ALL
+            .iter()
+            .find(|feature| feature.name() == name)
+            .copied()
+            .ok_or_else(|| Error::msg(format!("Unrecognized slope feature \"{}\"", name)))
+    }
+
+    /// Returns the character that stands for this feature in a slope atlas
+    /// file.
+    pub fn to_char(self) -> char {
+        match self {
+            SlopeFeature::Nothing => '.',
+            SlopeFeature::Tree => '#',
+            SlopeFeature::Rock => '*',
+            SlopeFeature::Ice => '~',
+            SlopeFeature::Mogul => '^',
+            SlopeFeature::LiftPylon => '|',
+        }
+    }
+
+    /// Returns the name of this feature, as accepted by `from_name(...)`.
+    pub fn name(self) -> &'static str {
+        match self {
+            SlopeFeature::Nothing => "nothing",
+            SlopeFeature::Tree => "tree",
+            SlopeFeature::Rock => "rock",
+            SlopeFeature::Ice => "ice",
+            SlopeFeature::Mogul => "mogul",
+            SlopeFeature::LiftPylon => "lift-pylon",
+        }
+    }
+
+    /// Returns `true` if a toboggan landing on this feature crashes into it,
+    /// rather than sliding over it.
+    pub fn is_obstacle(self) -> bool {
+        match self {
+            SlopeFeature::Tree | SlopeFeature::Rock | SlopeFeature::LiftPylon => true,
+            SlopeFeature::Nothing | SlopeFeature::Ice | SlopeFeature::Mogul => false,
         }
     }
 }
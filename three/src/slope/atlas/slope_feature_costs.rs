@@ -0,0 +1,59 @@
+use super::slope_feature::SlopeFeature;
+
+/// Weighs how costly it is for a toboggan to land on each kind of
+/// `SlopeFeature`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SlopeFeatureCosts {
+    /// Cost of landing on `SlopeFeature::Nothing`.
+    pub nothing: f64,
+    /// Cost of landing on `SlopeFeature::Tree`.
+    pub tree: f64,
+    /// Cost of landing on `SlopeFeature::Rock`.
+    pub rock: f64,
+    /// Cost of landing on `SlopeFeature::Ice`.
+    pub ice: f64,
+    /// Cost of landing on `SlopeFeature::Mogul`.
+    pub mogul: f64,
+    /// Cost of landing on `SlopeFeature::LiftPylon`.
+    pub lift_pylon: f64,
+}
+
+impl SlopeFeatureCosts {
+    /// Returns the cost of landing on `feature`.
+    pub fn cost_of(&self, feature: SlopeFeature) -> f64 {
+        match feature {
+            SlopeFeature::Nothing => self.nothing,
+            SlopeFeature::Tree => self.tree,
+            SlopeFeature::Rock => self.rock,
+            SlopeFeature::Ice => self.ice,
+            SlopeFeature::Mogul => self.mogul,
+            SlopeFeature::LiftPylon => self.lift_pylon,
+        }
+    }
+
+    /// Changes the cost of landing on `feature` to `cost`.
+    pub fn set_cost_of(&mut self, feature: SlopeFeature, cost: f64) {
+        match feature {
+            SlopeFeature::Nothing => self.nothing = cost,
+            SlopeFeature::Tree => self.tree = cost,
+            SlopeFeature::Rock => self.rock = cost,
+            SlopeFeature::Ice => self.ice = cost,
+            SlopeFeature::Mogul => self.mogul = cost,
+            SlopeFeature::LiftPylon => self.lift_pylon = cost,
+        }
+    }
+}
+
+impl Default for SlopeFeatureCosts {
+    /// Weighs every feature against a tree, which costs `1`.
+    fn default() -> SlopeFeatureCosts {
+        SlopeFeatureCosts {
+            nothing: 0.0,
+            tree: 1.0,
+            rock: 2.0,
+            ice: 0.5,
+            mogul: 0.25,
+            lift_pylon: 5.0,
+        }
+    }
+}
@@ -1 +1,3 @@
+pub mod path;
+pub mod simulation;
 pub mod trajectory;
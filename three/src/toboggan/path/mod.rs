@@ -0,0 +1,8 @@
+mod toboggan_path_planner;
+
+/// Sequence of positions visited by a steerable toboggan on its way down a
+/// slope.
+pub type TobogganPath = toboggan_path_planner::TobogganPath;
+
+/// Plans the way down a slope for a toboggan that can change lanes.
+pub type TobogganPathPlanner = toboggan_path_planner::TobogganPathPlanner;
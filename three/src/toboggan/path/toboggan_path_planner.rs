@@ -0,0 +1,328 @@
+use crate::slope::atlas::{SlopeAtlas, SlopeEdgeMode, SlopeFeature};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Sequence of positions visited by a steerable toboggan on its way down a
+/// slope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TobogganPath {
+    /// Every `(x, y)` position along this path, excluding the starting
+    /// position, one per contour, in the same terms as the positions visited
+    /// by a `TobogganTrajectoryDescender`.
+    pub positions: Vec<(i64, i64)>,
+    /// How many trees fall along this path.
+    pub tree_count: usize,
+}
+
+/// Plans the way down a slope for a toboggan that can change lanes.
+#[derive(Clone, Debug)]
+pub struct TobogganPathPlanner {
+    /// Every horizontal distance that the toboggan can move while it descends
+    /// a single contour.
+    pub lane_changes: Vec<i64>,
+}
+
+impl TobogganPathPlanner {
+    /// Creates a new `TobogganPathPlanner`.
+    ///
+    /// `lane_changes`: every horizontal distance that the toboggan can move
+    ///                 while it descends a single contour, like `-1`, `0`
+    ///                 and `1`.
+    pub fn new(lane_changes: Vec<i64>) -> TobogganPathPlanner {
+        TobogganPathPlanner { lane_changes }
+    }
+
+    /// Finds the path down the `slope_atlas` from `starting_x_position` on
+    /// its top contour to any position on its bottom contour along which the
+    /// fewest trees fall, using Dijkstra's algorithm.
+    ///
+    /// Past its edges, the slope behaves as described by its `edge_mode`:
+    /// lane changes that leave a bounded slope are not allowed, and lane
+    /// changes into a reflecting edge bounce off of it.
+    ///
+    /// Returns `None` if there is no way down, which only happens on a
+    /// bounded slope.
+    pub fn plan(&self, slope_atlas: &SlopeAtlas, starting_x_position: i64) -> Option<TobogganPath> {
+        let SlopeAtlas {
+            breadth,
+            height,
+            edge_mode,
+            ..
+        } = *slope_atlas;
+
+        // Positions that wrap around to the same feature are the same as far as
+        // the search is concerned, so each is identified by its feature index
+        // and its y-position.
+        let node_index =
+            |feature_index: i64, y_position: i64| (y_position * breadth + feature_index) as usize;
+        let mut tree_counts: Vec<Option<usize>> = vec![None; (breadth * height) as usize];
+        let mut positions: Vec<(i64, i64)> = vec![(0, 0); (breadth * height) as usize];
+        let mut predecessors: Vec<Option<usize>> = vec![None; (breadth * height) as usize];
+        let mut frontier = BinaryHeap::new();
+
+        let (starting_feature_index, _is_mirrored) =
+            edge_mode.align(starting_x_position, breadth)?;
+        let starting_node_index = node_index(starting_feature_index, 0);
+        tree_counts[starting_node_index] = Some(0);
+        positions[starting_node_index] = (
+            visited_x_position(edge_mode, starting_x_position, starting_feature_index),
+            0,
+        );
+        frontier.push(Reverse((0, starting_node_index)));
+
+        while let Some(Reverse((tree_count, current_node_index))) = frontier.pop() {
+            if tree_counts[current_node_index] != Some(tree_count) {
+                continue;
+            }
+
+            let (x_position, y_position) = positions[current_node_index];
+            if y_position == height - 1 {
+                let mut path_positions = vec![];
+                let mut path_node_index = current_node_index;
+                while let Some(predecessor_node_index) = predecessors[path_node_index] {
+                    path_positions.push(positions[path_node_index]);
+                    path_node_index = predecessor_node_index;
+                }
+                path_positions.reverse();
+
+                return Some(TobogganPath {
+                    positions: path_positions,
+                    tree_count,
+                });
+            }
+
+            let next_y_position = y_position + 1;
+            for lane_change in self.lane_changes.iter() {
+                let next_x_position = x_position + lane_change;
+                let (next_feature_index, _is_mirrored) =
+                    match edge_mode.align(next_x_position, breadth) {
+                        Some(alignment) => alignment,
+                        None => continue,
+                    };
+                let next_position = (
+                    visited_x_position(edge_mode, next_x_position, next_feature_index),
+                    next_y_position,
+                );
+
+                let next_tree_count = tree_count
+                    + match slope_atlas.feature_at(next_position) {
+                        Some(SlopeFeature::Tree) => 1,
+                        _ => 0,
+                    };
+
+                let next_node_index = node_index(next_feature_index, next_y_position);
+                if tree_counts[next_node_index]
+                    .is_none_or(|best_tree_count| next_tree_count < best_tree_count)
+                {
+                    tree_counts[next_node_index] = Some(next_tree_count);
+                    positions[next_node_index] = next_position;
+                    predecessors[next_node_index] = Some(current_node_index);
+                    frontier.push(Reverse((next_tree_count, next_node_index)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the x-position at which a toboggan moving to `x_position` ends up,
+/// given that it lands on the feature at `feature_index`.
+///
+/// Like a `TobogganTrajectoryDescender`, positions on a wrapping slope are
+/// left as they are, while positions on any other slope are brought back
+/// within its edges.
+fn visited_x_position(edge_mode: SlopeEdgeMode, x_position: i64, feature_index: i64) -> i64 {
+    match edge_mode {
+        SlopeEdgeMode::Wrap => x_position,
+        SlopeEdgeMode::Bounded | SlopeEdgeMode::Reflect => feature_index,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example slope atlas from https://adventofcode.com/2020/day/3.
+    const EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS: &str = include_str!("../../../files/test.txt");
+
+    /// Slope with a line of trees straight down the middle, which a toboggan
+    /// starting in the middle has to swerve to avoid.
+    const SWERVING_SLOPE_ATLAS_FILE_CONTENTS: &str = ".....\n..#..\n..#..\n..#..\n";
+
+    /// Every edge mode.
+    const EDGE_MODES: [SlopeEdgeMode; 3] = [
+        SlopeEdgeMode::Wrap,
+        SlopeEdgeMode::Bounded,
+        SlopeEdgeMode::Reflect,
+    ];
+
+    /// Lane changes that every test steers with.
+    const LANE_CHANGES: [i64; 3] = [-1, 0, 1];
+
+    /// Reads a slope atlas out of `slope_atlas_file_contents` with the given
+    /// `edge_mode`.
+    fn read_slope_atlas(slope_atlas_file_contents: &str, edge_mode: SlopeEdgeMode) -> SlopeAtlas {
+        let mut slope_atlas = SlopeAtlas::from_file(slope_atlas_file_contents).unwrap();
+        slope_atlas.edge_mode = edge_mode;
+
+        slope_atlas
+    }
+
+    /// Returns where a toboggan at `x_position` ends up after making
+    /// `lane_change`, or `None` if it leaves the slope.
+    fn change_lanes(slope_atlas: &SlopeAtlas, x_position: i64, lane_change: i64) -> Option<i64> {
+        let next_x_position = x_position + lane_change;
+        let (feature_index, _is_mirrored) = slope_atlas
+            .edge_mode
+            .align(next_x_position, slope_atlas.breadth)?;
+
+        Some(visited_x_position(
+            slope_atlas.edge_mode,
+            next_x_position,
+            feature_index,
+        ))
+    }
+
+    /// Finds the fewest trees along any path down `slope_atlas` by trying
+    /// every sequence of lane changes.
+    fn fewest_trees_by_brute_force(
+        slope_atlas: &SlopeAtlas,
+        x_position: i64,
+        y_position: i64,
+    ) -> Option<usize> {
+        if y_position == slope_atlas.height - 1 {
+            return Some(0);
+        }
+
+        LANE_CHANGES
+            .iter()
+            .filter_map(|lane_change| {
+                let next_x_position = change_lanes(slope_atlas, x_position, *lane_change)?;
+                let is_tree = slope_atlas.feature_at((next_x_position, y_position + 1))
+                    == Some(SlopeFeature::Tree);
+
+                fewest_trees_by_brute_force(slope_atlas, next_x_position, y_position + 1)
+                    .map(|tree_count| tree_count + is_tree as usize)
+            })
+            .min()
+    }
+
+    /// Asserts that `path` descends one contour at a time from
+    /// `starting_x_position` using only `LANE_CHANGES`, and that its tree
+    /// count adds up.
+    fn assert_well_formed(slope_atlas: &SlopeAtlas, starting_x_position: i64, path: &TobogganPath) {
+        assert_eq!(path.positions.len() as i64, slope_atlas.height - 1);
+
+        let mut x_position = change_lanes(slope_atlas, starting_x_position, 0).unwrap();
+        for (contour_index, (next_x_position, next_y_position)) in path.positions.iter().enumerate()
+        {
+            assert_eq!(*next_y_position, contour_index as i64 + 1);
+            assert!(
+                LANE_CHANGES.iter().any(|lane_change| {
+                    change_lanes(slope_atlas, x_position, *lane_change) == Some(*next_x_position)
+                }),
+                "{} to {} on a {:?} slope",
+                x_position,
+                next_x_position,
+                slope_atlas.edge_mode
+            );
+
+            x_position = *next_x_position;
+        }
+
+        assert_eq!(
+            path.tree_count,
+            path.positions
+                .iter()
+                .filter(|position| slope_atlas.feature_at(**position) == Some(SlopeFeature::Tree))
+                .count()
+        );
+    }
+
+    #[test]
+    fn swerves_around_trees() {
+        let path_planner = TobogganPathPlanner::new(LANE_CHANGES.to_vec());
+
+        for edge_mode in EDGE_MODES {
+            let slope_atlas = read_slope_atlas(SWERVING_SLOPE_ATLAS_FILE_CONTENTS, edge_mode);
+            let path = path_planner.plan(&slope_atlas, 2).unwrap();
+
+            assert_eq!(path.tree_count, 0, "{:?}", edge_mode);
+            assert_well_formed(&slope_atlas, 2, &path);
+        }
+
+        let straight_path_planner = TobogganPathPlanner::new(vec![0]);
+        let slope_atlas = read_slope_atlas(SWERVING_SLOPE_ATLAS_FILE_CONTENTS, SlopeEdgeMode::Wrap);
+        assert_eq!(
+            straight_path_planner
+                .plan(&slope_atlas, 2)
+                .unwrap()
+                .tree_count,
+            3
+        );
+    }
+
+    #[test]
+    fn finds_fewest_trees_along_any_path() {
+        let path_planner = TobogganPathPlanner::new(LANE_CHANGES.to_vec());
+
+        for edge_mode in EDGE_MODES {
+            let slope_atlas = read_slope_atlas(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS, edge_mode);
+
+            for starting_x_position in [0, 5, 10] {
+                let path = path_planner
+                    .plan(&slope_atlas, starting_x_position)
+                    .unwrap();
+
+                assert_eq!(
+                    Some(path.tree_count),
+                    fewest_trees_by_brute_force(&slope_atlas, starting_x_position, 0),
+                    "from {} on a {:?} slope",
+                    starting_x_position,
+                    edge_mode
+                );
+                assert_well_formed(&slope_atlas, starting_x_position, &path);
+            }
+        }
+    }
+
+    #[test]
+    fn finds_no_way_down_bounded_slope() {
+        let slope_atlas = read_slope_atlas(&".....\n".repeat(10), SlopeEdgeMode::Bounded);
+
+        assert_eq!(
+            TobogganPathPlanner::new(vec![1]).plan(&slope_atlas, 0),
+            None
+        );
+        assert_eq!(
+            TobogganPathPlanner::new(vec![-3, 3]).plan(&slope_atlas, 2),
+            None
+        );
+        assert_eq!(TobogganPathPlanner::new(vec![]).plan(&slope_atlas, 2), None);
+        assert_eq!(
+            TobogganPathPlanner::new(vec![0]).plan(&slope_atlas, 5),
+            None
+        );
+        assert!(TobogganPathPlanner::new(vec![-1, 1])
+            .plan(&slope_atlas, 0)
+            .is_some());
+    }
+
+    #[test]
+    fn plans_empty_path_down_single_contour() {
+        let path_planner = TobogganPathPlanner::new(LANE_CHANGES.to_vec());
+
+        for edge_mode in EDGE_MODES {
+            let slope_atlas = read_slope_atlas("#.#\n", edge_mode);
+
+            assert_eq!(
+                path_planner.plan(&slope_atlas, 0),
+                Some(TobogganPath {
+                    positions: vec![],
+                    tree_count: 0,
+                })
+            );
+        }
+    }
+}
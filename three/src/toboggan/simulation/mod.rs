@@ -0,0 +1,7 @@
+mod toboggan_simulation;
+
+/// Records toboggans landing on the same feature at the same time.
+pub type TobogganCollision = toboggan_simulation::TobogganCollision;
+
+/// Runs many toboggans down one slope at once, on a shared clock.
+pub type TobogganSimulation<'a> = toboggan_simulation::TobogganSimulation<'a>;
@@ -0,0 +1,315 @@
+use crate::slope::atlas::{SlopeAtlas, SlopeFeature};
+use crate::toboggan::trajectory::TobogganTrajectory;
+use anyhow::{Error, Result};
+use std::collections::HashMap;
+
+/// Records toboggans landing on the same feature at the same time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TobogganCollision {
+    /// Tick of the shared clock at which the toboggans collided.
+    pub tick: u64,
+    /// `(x, y)` pair describing the position of the feature that the
+    /// toboggans collided on, brought within the edges of the slope.
+    pub position: (i64, i64),
+    /// Index of every rider involved in the collision, in ascending order.
+    pub riders: Vec<usize>,
+}
+
+/// Runs many toboggans down one slope at once, on a shared clock.
+///
+/// Each rider appears at the starting position of its trajectory on its start
+/// tick, then moves to the next position along its trajectory every tick
+/// until it reaches the bottom of the slope.
+pub struct TobogganSimulation<'a> {
+    /// The slope being descended.
+    slope_atlas: &'a SlopeAtlas,
+    /// Trajectory of every rider, along with the tick at which it starts
+    /// moving, in order of addition.
+    riders: Vec<(TobogganTrajectory, u64)>,
+
+    /// Every collision so far, in order of occurrence.
+    collisions: Vec<TobogganCollision>,
+    /// How many trees have fallen along the trajectory of each rider so far.
+    tree_counts: Vec<usize>,
+    /// How many ticks have been simulated so far.
+    number_of_ticks: u64,
+}
+
+impl<'a> TobogganSimulation<'a> {
+    /// Creates a new `TobogganSimulation` without any riders down the slope
+    /// mapped out by `slope_atlas`.
+    pub fn new(slope_atlas: &'a SlopeAtlas) -> TobogganSimulation<'a> {
+        TobogganSimulation {
+            slope_atlas,
+            riders: vec![],
+            collisions: vec![],
+            tree_counts: vec![],
+            number_of_ticks: 0,
+        }
+    }
+
+    /// Adds a rider that follows `trajectory`, starting at `start_tick`.
+    ///
+    /// Riders are referred to by the order in which they were added, starting
+    /// at `0`.
+    ///
+    /// Returns `Err(...)` if `trajectory` moves down by less than `1`, since
+    /// then the rider never reaches the bottom of the slope.
+    pub fn add_rider(
+        &mut self,
+        trajectory: TobogganTrajectory,
+        start_tick: u64,
+    ) -> Result<&mut TobogganSimulation<'a>> {
+        let (_x_velocity, y_velocity) = trajectory.velocity();
+        if y_velocity < 1 {
+            return Err(Error::msg(format!(
+                "Rider following \"{}\" never reaches the bottom of the slope",
+                trajectory
+            )));
+        }
+
+        self.riders.push((trajectory, start_tick));
+
+        Ok(self)
+    }
+
+    /// Runs every rider down the slope, from tick `0` until the last one
+    /// reaches the bottom.
+    ///
+    /// Riders collide when they land on the same feature on the same tick,
+    /// even if they got there from opposite edges of a wrapping slope.
+    /// Riders starting at the same position on the same tick collide too.
+    /// Whenever no rider is on the slope, the clock skips ahead to the next
+    /// departure.
+    pub fn run(&mut self) -> &mut TobogganSimulation<'a> {
+        let mut descenders = self
+            .riders
+            .iter()
+            .map(|(trajectory, _start_tick)| Some(trajectory.descend(self.slope_atlas)))
+            .collect::<Vec<_>>();
+        self.collisions = vec![];
+        self.tree_counts = vec![0; self.riders.len()];
+        self.number_of_ticks = 0;
+
+        let mut tick = 0;
+        while descenders.iter().any(Option::is_some) {
+            let is_any_rider_descending = self.riders.iter().zip(descenders.iter()).any(
+                |((_trajectory, start_tick), descender)| *start_tick <= tick && descender.is_some(),
+            );
+            if !is_any_rider_descending {
+                // Every rider still to come has yet to depart, so nothing
+                // happens until the first of them does.
+                if let Some(next_start_tick) = self
+                    .riders
+                    .iter()
+                    .map(|(_trajectory, start_tick)| *start_tick)
+                    .filter(|start_tick| *start_tick > tick)
+                    .min()
+                {
+                    tick = next_start_tick;
+                }
+            }
+
+            let mut riders_by_position: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+
+            for (rider, ((trajectory, start_tick), descender)) in
+                self.riders.iter().zip(descenders.iter_mut()).enumerate()
+            {
+                let position = if tick < *start_tick {
+                    continue;
+                } else if tick == *start_tick {
+                    trajectory.starting_position()
+                } else {
+                    match descender.as_mut().and_then(Iterator::next) {
+                        Some(position) => {
+                            if self.slope_atlas.feature_at(position) == Some(SlopeFeature::Tree) {
+                                self.tree_counts[rider] += 1;
+                            }
+
+                            position
+                        }
+                        None => {
+                            *descender = None;
+                            continue;
+                        }
+                    }
+                };
+
+                let (x_position, y_position) = position;
+                if let Some((feature_index, _is_mirrored)) = self
+                    .slope_atlas
+                    .edge_mode
+                    .align(x_position, self.slope_atlas.breadth)
+                {
+                    riders_by_position
+                        .entry((feature_index, y_position))
+                        .or_default()
+                        .push(rider);
+                }
+            }
+
+            let mut collisions = riders_by_position
+                .into_iter()
+                .filter(|(_position, riders)| riders.len() > 1)
+                .map(|(position, riders)| TobogganCollision {
+                    tick,
+                    position,
+                    riders,
+                })
+                .collect::<Vec<TobogganCollision>>();
+            collisions.sort_by_key(|collision| (collision.position.1, collision.position.0));
+            self.collisions.extend(collisions);
+
+            tick += 1;
+        }
+
+        // The last tick is the one on which every rider had already finished.
+        self.number_of_ticks = tick.saturating_sub(1);
+
+        self
+    }
+
+    /// Returns every collision of the last run, in order of occurrence.
+    pub fn collisions(&self) -> &[TobogganCollision] {
+        &self.collisions
+    }
+
+    /// Returns how many trees fell along the trajectory of each rider during
+    /// the last run, in order of addition.
+    pub fn tree_counts(&self) -> &[usize] {
+        &self.tree_counts
+    }
+
+    /// Returns how many ticks it took for every rider to reach the bottom
+    /// during the last run.
+    pub fn number_of_ticks(&self) -> u64 {
+        self.number_of_ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example slope atlas from https://adventofcode.com/2020/day/3.
+    const EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS: &str = include_str!("../../../files/test.txt");
+
+    /// Slope without any features, five wide and six tall.
+    const EMPTY_SLOPE_ATLAS_FILE_CONTENTS: &str = ".....\n.....\n.....\n.....\n.....\n.....\n";
+
+    /// Counts the trees along `trajectory` one position at a time.
+    fn number_of_trees_along_trajectory(
+        slope_atlas: &SlopeAtlas,
+        trajectory: &TobogganTrajectory,
+    ) -> usize {
+        trajectory
+            .descend(slope_atlas)
+            .filter(|position| slope_atlas.feature_at(*position) == Some(SlopeFeature::Tree))
+            .count()
+    }
+
+    #[test]
+    fn rejects_riders_that_never_reach_the_bottom() {
+        let slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+        let mut toboggan_simulation = TobogganSimulation::new(&slope_atlas);
+
+        assert!(toboggan_simulation
+            .add_rider(TobogganTrajectory::new((0, 0), (3, 0)), 0)
+            .is_err());
+        assert!(toboggan_simulation
+            .add_rider(TobogganTrajectory::new((0, 5), (1, -1)), 0)
+            .is_err());
+        assert!(toboggan_simulation
+            .add_rider(TobogganTrajectory::new((0, 0), (0, 1)), 0)
+            .is_ok());
+    }
+
+    #[test]
+    fn collides_riders_through_wraparound() {
+        let slope_atlas = SlopeAtlas::from_file(EMPTY_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+        let mut toboggan_simulation = TobogganSimulation::new(&slope_atlas);
+        toboggan_simulation
+            .add_rider(TobogganTrajectory::new((1, 0), (1, 1)), 0)
+            .unwrap()
+            .add_rider(
+                TobogganTrajectory::new((1 + slope_atlas.breadth, 0), (1, 1)),
+                0,
+            )
+            .unwrap()
+            .run();
+
+        assert_eq!(
+            toboggan_simulation.collisions(),
+            (0..slope_atlas.height)
+                .map(|y_position| TobogganCollision {
+                    tick: y_position as u64,
+                    position: ((1 + y_position) % slope_atlas.breadth, y_position),
+                    riders: vec![0, 1],
+                })
+                .collect::<Vec<TobogganCollision>>()
+        );
+        assert_eq!(toboggan_simulation.number_of_ticks(), 6);
+    }
+
+    #[test]
+    fn skips_idle_gap_between_staggered_riders() {
+        let slope_atlas = SlopeAtlas::from_file(EMPTY_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+
+        for later_start_tick in [10, 1_000_000_000_000] {
+            let mut toboggan_simulation = TobogganSimulation::new(&slope_atlas);
+            toboggan_simulation
+                .add_rider(TobogganTrajectory::new((0, 0), (1, 1)), 0)
+                .unwrap()
+                .add_rider(TobogganTrajectory::new((0, 0), (1, 1)), later_start_tick)
+                .unwrap()
+                .add_rider(TobogganTrajectory::new((0, 0), (0, 2)), later_start_tick)
+                .unwrap()
+                .run();
+
+            assert_eq!(
+                toboggan_simulation.collisions(),
+                [TobogganCollision {
+                    tick: later_start_tick,
+                    position: (0, 0),
+                    riders: vec![1, 2],
+                }]
+            );
+            assert_eq!(
+                toboggan_simulation.number_of_ticks(),
+                later_start_tick + slope_atlas.height as u64
+            );
+            assert_eq!(toboggan_simulation.tree_counts(), [0, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn counts_the_same_trees_as_each_rider_descending_alone() {
+        let slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+        let trajectories = [
+            TobogganTrajectory::new((0, 0), (1, 1)),
+            TobogganTrajectory::new((0, 0), (3, 1)),
+            TobogganTrajectory::new((0, 0), (5, 1)),
+            TobogganTrajectory::new((0, 0), (7, 1)),
+            TobogganTrajectory::new((0, 0), (1, 2)),
+            TobogganTrajectory::new((4, 3), (-2, 1)),
+            TobogganTrajectory::new((-7, -4), (13, 3)),
+        ];
+
+        let mut toboggan_simulation = TobogganSimulation::new(&slope_atlas);
+        for (rider, trajectory) in trajectories.iter().enumerate() {
+            toboggan_simulation
+                .add_rider(*trajectory, 3 * rider as u64)
+                .unwrap();
+        }
+        toboggan_simulation.run();
+
+        assert_eq!(
+            toboggan_simulation.tree_counts(),
+            trajectories
+                .iter()
+                .map(|trajectory| number_of_trees_along_trajectory(&slope_atlas, trajectory))
+                .collect::<Vec<usize>>()
+        );
+        assert_eq!(&toboggan_simulation.tree_counts()[..5], [2, 7, 3, 4, 2]);
+    }
+}
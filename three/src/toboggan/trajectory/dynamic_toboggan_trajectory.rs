@@ -0,0 +1,317 @@
+use super::toboggan_physics::TobogganPhysics;
+use crate::slope::atlas::{SlopeAtlas, SlopeEdgeMode};
+use std::fmt::{Display, Formatter};
+
+/// Below this down speed, a toboggan is considered to have stopped.
+const STOPPED_Y_VELOCITY: f64 = 1e-3;
+
+/// Indicates a descent down a slope that speeds up and slows down as the
+/// toboggan is acted on by `TobogganPhysics`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicTobogganTrajectory {
+    /// `(x, y)` pair describing the starting position of the toboggan before descent begins.
+    starting_position: (i64, i64),
+    /// `(x, y)` pair describing the direction and magnitude of descent down
+    /// the slope before descent begins.
+    initial_velocity: (f64, f64),
+    /// Forces acting on the toboggan during descent.
+    physics: TobogganPhysics,
+}
+
+impl DynamicTobogganTrajectory {
+    /// Creates a new `DynamicTobogganTrajectory`.
+    ///
+    /// `starting_position`:    `(x, y)` pair describing the starting position
+    ///                         of the toboggan before descent begins.
+    ///
+    /// `initial_velocity`:     `(x, y)` pair describing the direction and
+    ///                         magnitude of descent down the slope before
+    ///                         descent begins.
+    ///
+    /// `physics`:              forces acting on the toboggan during descent.
+    pub fn new(
+        starting_position: (i64, i64),
+        initial_velocity: (f64, f64),
+        physics: TobogganPhysics,
+    ) -> DynamicTobogganTrajectory {
+        DynamicTobogganTrajectory {
+            starting_position,
+            initial_velocity,
+            physics,
+        }
+    }
+
+    /// Begins a descent down the slope mapped out by `slope_atlas`.
+    ///
+    /// Each tick, gravity pulls the toboggan down the slope, friction slows
+    /// it down, and it moves by its velocity. Its position is then rounded to
+    /// the nearest feature, and landing on a different feature than before
+    /// scales its velocity by the speed factor of that feature.
+    ///
+    /// The descent ends at the bottom of the slope, as soon as the toboggan
+    /// stops moving down the slope after gravity and friction have acted on
+    /// it, or as soon as the toboggan leaves a slope whose `edge_mode` is
+    /// `SlopeEdgeMode::Bounded`.
+    /// The toboggan bounces off the edges of a slope whose `edge_mode` is
+    /// `SlopeEdgeMode::Reflect`, mirroring its horizontal velocity.
+    pub fn descend<'a>(
+        &'a self,
+        slope_atlas: &'a SlopeAtlas,
+    ) -> DynamicTobogganTrajectoryDescender<'a> {
+        DynamicTobogganTrajectoryDescender {
+            current_position: (
+                self.starting_position.0 as f64,
+                self.starting_position.1 as f64,
+            ),
+            current_velocity: self.initial_velocity,
+            landed_position: self.starting_position,
+            physics: &self.physics,
+            slope_atlas,
+        }
+    }
+}
+
+impl Display for DynamicTobogganTrajectory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Right {}, down {}, with gravity {} and friction {}",
+            self.initial_velocity.0,
+            self.initial_velocity.1,
+            self.physics.gravity,
+            self.physics.friction
+        )
+    }
+}
+
+/// Iterates through the position of the toboggan at every tick along a
+/// `DynamicTobogganTrajectory`, along with its velocity there.
+///
+/// A slow toboggan can stay at the same position for several ticks.
+pub struct DynamicTobogganTrajectoryDescender<'a> {
+    /// The current, unrounded position of the ongoing descent.
+    current_position: (f64, f64),
+    /// The current velocity of the ongoing descent.
+    current_velocity: (f64, f64),
+    /// The position of the feature that the toboggan last landed on.
+    landed_position: (i64, i64),
+    /// Forces acting on the toboggan.
+    physics: &'a TobogganPhysics,
+    /// The slope being descended.
+    slope_atlas: &'a SlopeAtlas,
+}
+
+impl Iterator for DynamicTobogganTrajectoryDescender<'_> {
+    type Item = ((i64, i64), (f64, f64));
+
+    // Advances to the next tick of the descent.
+    fn next(&mut self) -> Option<((i64, i64), (f64, f64))> {
+        let (mut x_velocity, mut y_velocity) = self.current_velocity;
+
+        y_velocity += self.physics.gravity;
+        x_velocity *= 1.0 - self.physics.friction;
+        y_velocity *= 1.0 - self.physics.friction;
+
+        // A toboggan that is not moving down the slope would otherwise keep
+        // on ticking without ever reaching the bottom.
+        if y_velocity < STOPPED_Y_VELOCITY {
+            return None;
+        }
+
+        let (mut x_position, mut y_position) = self.current_position;
+        x_position += x_velocity;
+        y_position += y_velocity;
+
+        if y_position.round() as i64 > self.slope_atlas.height - 1 {
+            return None;
+        }
+
+        // Features are centered on whole positions, so the edges of the slope
+        // lie half a feature past its first and last features.
+        let breadth = self.slope_atlas.breadth as f64;
+        match self.slope_atlas.edge_mode {
+            SlopeEdgeMode::Wrap => {}
+            SlopeEdgeMode::Bounded => {
+                if x_position < -0.5 || x_position >= breadth - 0.5 {
+                    return None;
+                }
+            }
+            SlopeEdgeMode::Reflect => {
+                let unmirrored_x_position = (x_position + 0.5).rem_euclid(2.0 * breadth) - 0.5;
+
+                if unmirrored_x_position < breadth - 0.5 {
+                    x_position = unmirrored_x_position;
+                } else {
+                    x_position = 2.0 * breadth - 1.0 - unmirrored_x_position;
+                    x_velocity = -x_velocity;
+                }
+            }
+        }
+
+        let position = (x_position.round() as i64, y_position.round() as i64);
+        if position != self.landed_position {
+            if let Some(feature) = self.slope_atlas.feature_at(position) {
+                let speed_factor = self.physics.speed_factor_of(feature);
+
+                x_velocity *= speed_factor;
+                y_velocity *= speed_factor;
+            }
+
+            self.landed_position = position;
+        }
+
+        self.current_position = (x_position, y_position);
+        self.current_velocity = (x_velocity, y_velocity);
+
+        Some((position, self.current_velocity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toboggan::trajectory::TobogganTrajectory;
+
+    /// Example slope atlas from https://adventofcode.com/2020/day/3.
+    const EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS: &str = include_str!("../../../files/test.txt");
+
+    /// More ticks than any descent in these tests should take, so that a
+    /// descent that never ends fails instead of hanging.
+    const MAX_NUMBER_OF_TICKS: usize = 10_000;
+
+    /// Every edge mode.
+    const EDGE_MODES: [SlopeEdgeMode; 3] = [
+        SlopeEdgeMode::Wrap,
+        SlopeEdgeMode::Bounded,
+        SlopeEdgeMode::Reflect,
+    ];
+
+    /// Reads a slope atlas out of `slope_atlas_file_contents` with the given
+    /// `edge_mode`.
+    fn read_slope_atlas(slope_atlas_file_contents: &str, edge_mode: SlopeEdgeMode) -> SlopeAtlas {
+        let mut slope_atlas = SlopeAtlas::from_file(slope_atlas_file_contents).unwrap();
+        slope_atlas.edge_mode = edge_mode;
+
+        slope_atlas
+    }
+
+    /// Returns every tick of the descent of `trajectory` down `slope_atlas`,
+    /// failing if it takes more than `MAX_NUMBER_OF_TICKS`.
+    fn descend(
+        trajectory: &DynamicTobogganTrajectory,
+        slope_atlas: &SlopeAtlas,
+    ) -> Vec<((i64, i64), (f64, f64))> {
+        let ticks = trajectory
+            .descend(slope_atlas)
+            .take(MAX_NUMBER_OF_TICKS + 1)
+            .collect::<Vec<((i64, i64), (f64, f64))>>();
+        assert!(ticks.len() <= MAX_NUMBER_OF_TICKS, "{}", trajectory);
+
+        ticks
+    }
+
+    #[test]
+    fn ends_descent_against_gravity() {
+        let slope_atlas = read_slope_atlas(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS, SlopeEdgeMode::Wrap);
+
+        for (gravity, friction) in [(-0.1, 0.0), (-1.0, 0.5), (-0.01, 0.9)] {
+            let physics = TobogganPhysics::new(gravity, friction).unwrap();
+            let trajectory = DynamicTobogganTrajectory::new((0, 0), (3.0, 1.0), physics);
+            let ticks = descend(&trajectory, &slope_atlas);
+
+            assert!(ticks
+                .iter()
+                .all(|(_position, (_x_velocity, y_velocity))| *y_velocity > 0.0));
+        }
+    }
+
+    #[test]
+    fn ends_descent_without_gravity_once_stopped() {
+        let slope_atlas = read_slope_atlas(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS, SlopeEdgeMode::Wrap);
+        let physics = TobogganPhysics::new(0.0, 0.9).unwrap();
+        let trajectory = DynamicTobogganTrajectory::new((0, 0), (1.0, 1.0), physics);
+
+        let ticks = descend(&trajectory, &slope_atlas);
+        let ((_x_position, y_position), _velocity) = ticks.last().unwrap();
+
+        assert!(*y_position < slope_atlas.height - 1);
+    }
+
+    #[test]
+    fn lands_on_the_slope_every_tick() {
+        for edge_mode in EDGE_MODES {
+            let slope_atlas = read_slope_atlas(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS, edge_mode);
+
+            for (gravity, friction) in [(0.0, 0.0), (0.3, 0.1), (1.0, 0.5), (0.05, 0.0)] {
+                for initial_velocity in [(0.0, 0.0), (2.7, 0.4), (-1.3, 1.0), (7.5, 2.5)] {
+                    let physics = TobogganPhysics::new(gravity, friction).unwrap();
+                    let trajectory =
+                        DynamicTobogganTrajectory::new((5, 0), initial_velocity, physics);
+                    let ticks = descend(&trajectory, &slope_atlas);
+
+                    let mut previous_y_position = 0;
+                    for ((x_position, y_position), _velocity) in ticks {
+                        assert!(
+                            (0..slope_atlas.height).contains(&y_position),
+                            "{}",
+                            trajectory
+                        );
+                        assert!(y_position >= previous_y_position, "{}", trajectory);
+                        if edge_mode != SlopeEdgeMode::Wrap {
+                            assert!(
+                                (0..slope_atlas.breadth).contains(&x_position),
+                                "{} on a {:?} slope",
+                                trajectory,
+                                edge_mode
+                            );
+                        }
+
+                        previous_y_position = y_position;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn follows_integer_trajectory_without_forces() {
+        let empty_slope_atlas_file_contents = ".....\n".repeat(10);
+
+        for edge_mode in EDGE_MODES {
+            let slope_atlas = read_slope_atlas(&empty_slope_atlas_file_contents, edge_mode);
+            let physics = TobogganPhysics::new(0.0, 0.0).unwrap();
+            let trajectory = DynamicTobogganTrajectory::new((0, 0), (2.0, 1.0), physics);
+
+            let positions = descend(&trajectory, &slope_atlas)
+                .into_iter()
+                .map(|(position, _velocity)| position)
+                .collect::<Vec<(i64, i64)>>();
+
+            assert_eq!(
+                positions,
+                TobogganTrajectory::new((0, 0), (2, 1))
+                    .descend(&slope_atlas)
+                    .collect::<Vec<(i64, i64)>>(),
+                "{:?}",
+                edge_mode
+            );
+        }
+    }
+
+    #[test]
+    fn flips_horizontal_velocity_off_reflecting_edges() {
+        let slope_atlas = read_slope_atlas(&".....\n".repeat(10), SlopeEdgeMode::Reflect);
+        let physics = TobogganPhysics::new(0.0, 0.0).unwrap();
+        let trajectory = DynamicTobogganTrajectory::new((0, 0), (2.0, 1.0), physics);
+
+        let x_velocities = descend(&trajectory, &slope_atlas)
+            .into_iter()
+            .map(|(_position, (x_velocity, _y_velocity))| x_velocity)
+            .collect::<Vec<f64>>();
+
+        assert_eq!(
+            x_velocities,
+            [2.0, 2.0, -2.0, -2.0, 2.0, 2.0, 2.0, -2.0, -2.0]
+        );
+    }
+}
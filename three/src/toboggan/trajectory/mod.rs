@@ -1,4 +1,23 @@
+mod dynamic_toboggan_trajectory;
+mod toboggan_physics;
 mod toboggan_trajectory;
+mod toboggan_trajectory_search;
+mod toboggan_trajectory_tree_counter;
 
 /// Indicates a direction of descent down a slope.
 pub type TobogganTrajectory = toboggan_trajectory::TobogganTrajectory;
+
+/// Ranks every trajectory within some bounds on velocity by how many trees
+/// fall along it.
+pub type TobogganTrajectorySearch = toboggan_trajectory_search::TobogganTrajectorySearch;
+
+/// Counts the trees along many trajectories down a wrapping slope at once.
+pub type TobogganTrajectoryTreeCounter =
+    toboggan_trajectory_tree_counter::TobogganTrajectoryTreeCounter;
+
+/// Indicates a descent down a slope that speeds up and slows down as the
+/// toboggan is acted on by `TobogganPhysics`.
+pub type DynamicTobogganTrajectory = dynamic_toboggan_trajectory::DynamicTobogganTrajectory;
+
+/// Describes the forces acting on a toboggan as it descends a slope.
+pub type TobogganPhysics = toboggan_physics::TobogganPhysics;
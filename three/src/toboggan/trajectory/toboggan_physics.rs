@@ -0,0 +1,99 @@
+use crate::slope::atlas::SlopeFeature;
+use anyhow::{Error, Result};
+use std::collections::HashMap;
+
+/// Describes the forces acting on a toboggan as it descends a slope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TobogganPhysics {
+    /// How much faster the toboggan moves down the slope after each tick.
+    pub gravity: f64,
+    /// Fraction of its velocity that the toboggan loses to friction each
+    /// tick, from `0` up to but not including `1`.
+    pub friction: f64,
+    /// How much the velocity of the toboggan is scaled by when it lands on
+    /// each kind of `SlopeFeature`. Features without a factor leave the
+    /// velocity as it is.
+    pub speed_factors: HashMap<SlopeFeature, f64>,
+}
+
+impl TobogganPhysics {
+    /// Creates a new `TobogganPhysics` with the given `gravity` and
+    /// `friction`, and the default effect of landing on each kind of
+    /// `SlopeFeature`.
+    ///
+    /// Returns `Err(...)` if `gravity` is not a finite number, or if
+    /// `friction` is not from `0` up to but not including `1`: a toboggan
+    /// that loses all of its velocity every tick never gets anywhere, and one
+    /// that loses more than all of it, or gains from friction, goes back up
+    /// the slope.
+    pub fn new(gravity: f64, friction: f64) -> Result<TobogganPhysics> {
+        if !gravity.is_finite() {
+            return Err(Error::msg(format!(
+                "Gravity must be a finite number, not {}",
+                gravity
+            )));
+        }
+        if !(0.0..1.0).contains(&friction) {
+            return Err(Error::msg(format!(
+                "Friction must be from 0 up to but not including 1, not {}",
+                friction
+            )));
+        }
+
+        Ok(TobogganPhysics {
+            gravity,
+            friction,
+            speed_factors: [
+                (SlopeFeature::Tree, 0.25),
+                (SlopeFeature::Rock, 0.5),
+                (SlopeFeature::Ice, 1.5),
+                (SlopeFeature::Mogul, 0.75),
+                (SlopeFeature::LiftPylon, 0.0),
+            ]
+            .iter()
+            .copied()
+            .collect(),
+        })
+    }
+
+    /// Returns how much the velocity of the toboggan is scaled by when it
+    /// lands on `feature`.
+    pub fn speed_factor_of(&self, feature: SlopeFeature) -> f64 {
+        self.speed_factors.get(&feature).copied().unwrap_or(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_friction_from_zero_up_to_one() {
+        for friction in [0.0, 0.5, 0.999] {
+            assert!(TobogganPhysics::new(1.0, friction).is_ok());
+        }
+        assert!(TobogganPhysics::new(-1.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn rejects_friction_outside_zero_up_to_one() {
+        for friction in [1.0, 1.5, -0.1, f64::NAN, f64::INFINITY] {
+            assert!(
+                TobogganPhysics::new(1.0, friction).is_err(),
+                "friction {}",
+                friction
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_gravity_that_is_not_finite() {
+        for gravity in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+            assert!(
+                TobogganPhysics::new(gravity, 0.5).is_err(),
+                "gravity {}",
+                gravity
+            );
+        }
+    }
+}
@@ -1,7 +1,8 @@
+use crate::slope::atlas::{SlopeAtlas, SlopeEdgeMode};
 use std::fmt::{Display, Formatter};
 
 /// Indicates a direction of descent down a slope.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct TobogganTrajectory {
     /// `(x, y)` pair describing the starting position of the toboggan before descent begins.
     starting_position: (i64, i64),
@@ -24,12 +25,31 @@ impl TobogganTrajectory {
         }
     }
 
-    /// Begins a descent down a slope.
-    pub fn descend(&self, slope_height: i64) -> TobogganTrajectoryDescender {
+    /// `(x, y)` pair describing the starting position of the toboggan before
+    /// descent begins.
+    pub fn starting_position(&self) -> (i64, i64) {
+        self.starting_position
+    }
+
+    /// `(x, y)` pair describing the direction and magnitude of descent down
+    /// the slope.
+    pub fn velocity(&self) -> (i64, i64) {
+        self.velocity
+    }
+
+    /// Begins a descent down the slope mapped out by `slope_atlas`.
+    ///
+    /// The descent ends at the bottom of the slope, or as soon as the
+    /// toboggan leaves a slope whose `edge_mode` is `SlopeEdgeMode::Bounded`.
+    /// The toboggan bounces off the edges of a slope whose `edge_mode` is
+    /// `SlopeEdgeMode::Reflect`, mirroring its horizontal velocity.
+    pub fn descend(&self, slope_atlas: &SlopeAtlas) -> TobogganTrajectoryDescender {
         TobogganTrajectoryDescender {
+            breadth: slope_atlas.breadth,
             current_x_position: self.starting_position.0,
             current_y_position: self.starting_position.1,
-            max_y_position: slope_height - 1,
+            edge_mode: slope_atlas.edge_mode,
+            max_y_position: slope_atlas.height - 1,
             velocity: self.velocity,
         }
     }
@@ -43,10 +63,14 @@ impl Display for TobogganTrajectory {
 
 /// Iterates through every position along a `TobogganTrajectory`.
 pub struct TobogganTrajectoryDescender {
+    /// How wide the slope is.
+    breadth: i64,
     /// The current x-position of the ongoing descent.
     current_x_position: i64,
     /// The current y-position of the ongoing descent.
     current_y_position: i64,
+    /// How the slope behaves past its left and right edges.
+    edge_mode: SlopeEdgeMode,
     /// Largest valid `current_y_position`.
     max_y_position: i64,
     /// `(x, y)` pair describing the direction and magnitude of descent down the slope.
@@ -63,10 +87,89 @@ impl Iterator for TobogganTrajectoryDescender {
         self.current_x_position += x_velocity;
         self.current_y_position += y_velocity;
 
-        if self.current_y_position <= self.max_y_position {
-            Some((self.current_x_position, self.current_y_position))
-        } else {
-            None
+        if self.current_y_position > self.max_y_position {
+            return None;
+        }
+
+        match self.edge_mode {
+            SlopeEdgeMode::Wrap => {}
+            SlopeEdgeMode::Bounded | SlopeEdgeMode::Reflect => {
+                let (x_position, is_mirrored) = self
+                    .edge_mode
+                    .align(self.current_x_position, self.breadth)?;
+
+                self.current_x_position = x_position;
+                if is_mirrored {
+                    self.velocity = (-x_velocity, y_velocity);
+                }
+            }
         }
+
+        Some((self.current_x_position, self.current_y_position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns every position along the descent of a toboggan moving right
+    /// by `2` and down by `1` from the top left corner of an empty slope, `5`
+    /// features wide and `10` tall, whose `edge_mode` is as given.
+    fn descend_empty_slope(edge_mode: SlopeEdgeMode) -> Vec<(i64, i64)> {
+        let mut slope_atlas = SlopeAtlas::from_file(&".....\n".repeat(10)).unwrap();
+        slope_atlas.edge_mode = edge_mode;
+
+        TobogganTrajectory::new((0, 0), (2, 1))
+            .descend(&slope_atlas)
+            .collect()
+    }
+
+    #[test]
+    fn descends_past_edges_of_wrapping_slope() {
+        assert_eq!(
+            descend_empty_slope(SlopeEdgeMode::Wrap),
+            (1..10)
+                .map(|y_position| (2 * y_position, y_position))
+                .collect::<Vec<(i64, i64)>>()
+        );
+    }
+
+    #[test]
+    fn ends_descent_at_edge_of_bounded_slope() {
+        assert_eq!(
+            descend_empty_slope(SlopeEdgeMode::Bounded),
+            [(2, 1), (4, 2)]
+        );
+    }
+
+    #[test]
+    fn bounces_off_edges_of_reflecting_slope() {
+        assert_eq!(
+            descend_empty_slope(SlopeEdgeMode::Reflect),
+            [
+                (2, 1),
+                (4, 2),
+                (3, 3),
+                (1, 4),
+                (0, 5),
+                (2, 6),
+                (4, 7),
+                (3, 8),
+                (1, 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn ends_descent_at_bottom_of_slope() {
+        let slope_atlas = SlopeAtlas::from_file(&".....\n".repeat(10)).unwrap();
+
+        assert_eq!(
+            TobogganTrajectory::new((0, 0), (1, 3))
+                .descend(&slope_atlas)
+                .collect::<Vec<(i64, i64)>>(),
+            [(1, 3), (2, 6), (3, 9)]
+        );
     }
 }
@@ -0,0 +1,208 @@
+use super::toboggan_trajectory::{TobogganTrajectory, TobogganTrajectoryDescender};
+use crate::slope::atlas::{SlopeAtlas, SlopeFeature};
+use std::iter::Peekable;
+use std::ops::RangeInclusive;
+
+/// Ranks every trajectory within some bounds on velocity by how many trees
+/// fall along it.
+#[derive(Debug)]
+pub struct TobogganTrajectorySearch {
+    /// Every trajectory searched, along with how many trees fall along it,
+    /// sorted in ascending order by tree count.
+    ///
+    /// Trajectories with the same tree count are ordered by right speed, then
+    /// by down speed.
+    ranked_trajectories: Vec<(TobogganTrajectory, usize)>,
+}
+
+impl TobogganTrajectorySearch {
+    /// Counts the trees along every trajectory down the `slope_atlas` that
+    /// begins at `starting_position` and moves right by one of the
+    /// `x_velocities` and down by one of the `y_velocities`.
+    ///
+    /// Every trajectory descends in lockstep, so the `slope_atlas` is only
+    /// scanned once. Down speeds less than `1` never reach the bottom of the
+    /// slope, and are skipped.
+    pub fn new(
+        slope_atlas: &SlopeAtlas,
+        starting_position: (i64, i64),
+        x_velocities: RangeInclusive<i64>,
+        y_velocities: RangeInclusive<i64>,
+    ) -> TobogganTrajectorySearch {
+        let mut searched_trajectories = x_velocities
+            .flat_map(|x_velocity| {
+                y_velocities
+                    .clone()
+                    .filter(|y_velocity| *y_velocity >= 1)
+                    .map(move |y_velocity| {
+                        TobogganTrajectory::new(starting_position, (x_velocity, y_velocity))
+                    })
+            })
+            .map(|trajectory| {
+                (
+                    trajectory,
+                    trajectory.descend(slope_atlas).peekable(),
+                    0usize,
+                )
+            })
+            .collect::<Vec<(
+                TobogganTrajectory,
+                Peekable<TobogganTrajectoryDescender>,
+                usize,
+            )>>();
+
+        for y_position in 0..slope_atlas.height {
+            for (_trajectory, descender, tree_count) in searched_trajectories.iter_mut() {
+                // Trajectories that start above the top of the slope pass over
+                // positions that are not on any contour.
+                while descender
+                    .next_if(|(_x_position, next_y_position)| *next_y_position < y_position)
+                    .is_some()
+                {}
+
+                if let Some(position) = descender
+                    .next_if(|(_x_position, next_y_position)| *next_y_position == y_position)
+                {
+                    if slope_atlas.feature_at(position) == Some(SlopeFeature::Tree) {
+                        *tree_count += 1;
+                    }
+                }
+            }
+        }
+
+        let mut ranked_trajectories = searched_trajectories
+            .into_iter()
+            .map(|(trajectory, _descender, tree_count)| (trajectory, tree_count))
+            .collect::<Vec<(TobogganTrajectory, usize)>>();
+        ranked_trajectories.sort_by_key(|(_trajectory, tree_count)| *tree_count);
+
+        TobogganTrajectorySearch {
+            ranked_trajectories,
+        }
+    }
+
+    /// Returns the trajectory along which the fewest trees fall, along with
+    /// how many that is, or `None` if no trajectories were searched.
+    pub fn best(&self) -> Option<(&TobogganTrajectory, usize)> {
+        self.top(1).next()
+    }
+
+    /// Iterates through the `k` trajectories along which the fewest trees
+    /// fall, in ascending order by tree count, along with how many that is.
+    pub fn top(&self, k: usize) -> impl Iterator<Item = (&TobogganTrajectory, usize)> {
+        self.ranked_trajectories
+            .iter()
+            .take(k)
+            .map(|(trajectory, tree_count)| (trajectory, *tree_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slope::atlas::SlopeEdgeMode;
+
+    /// Example slope atlas from https://adventofcode.com/2020/day/3.
+    const EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS: &str = include_str!("../../../files/test.txt");
+
+    /// Counts the trees along `trajectory` one position at a time.
+    fn number_of_trees_along_trajectory(
+        slope_atlas: &SlopeAtlas,
+        trajectory: &TobogganTrajectory,
+    ) -> usize {
+        trajectory
+            .descend(slope_atlas)
+            .filter(|position| slope_atlas.feature_at(*position) == Some(SlopeFeature::Tree))
+            .count()
+    }
+
+    #[test]
+    fn agrees_with_each_trajectory_descending_alone() {
+        let mut slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+
+        for edge_mode in [
+            SlopeEdgeMode::Wrap,
+            SlopeEdgeMode::Bounded,
+            SlopeEdgeMode::Reflect,
+        ] {
+            slope_atlas.edge_mode = edge_mode;
+
+            for starting_position in [(0, 0), (3, 2), (0, -1), (0, -5), (4, -12)] {
+                let trajectory_search =
+                    TobogganTrajectorySearch::new(&slope_atlas, starting_position, -3..=25, 1..=3);
+                let ranked_trajectories = trajectory_search
+                    .top(usize::MAX)
+                    .collect::<Vec<(&TobogganTrajectory, usize)>>();
+
+                assert_eq!(ranked_trajectories.len(), 29 * 3);
+                for (trajectory, tree_count) in ranked_trajectories.iter() {
+                    assert_eq!(trajectory.starting_position(), starting_position);
+                    assert_eq!(
+                        *tree_count,
+                        number_of_trees_along_trajectory(&slope_atlas, trajectory),
+                        "{:?} from {:?} on a {:?} slope",
+                        trajectory.velocity(),
+                        starting_position,
+                        edge_mode
+                    );
+                }
+                assert!(ranked_trajectories
+                    .windows(2)
+                    .all(|window| window[0].1 <= window[1].1));
+
+                let (best_trajectory, best_tree_count) = trajectory_search.best().unwrap();
+                assert_eq!(
+                    best_tree_count,
+                    number_of_trees_along_trajectory(&slope_atlas, best_trajectory)
+                );
+                assert_eq!(
+                    best_tree_count,
+                    ranked_trajectories
+                        .iter()
+                        .map(|(_trajectory, tree_count)| *tree_count)
+                        .min()
+                        .unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn counts_trees_below_starting_position_above_slope() {
+        let slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+        let trajectory_search =
+            TobogganTrajectorySearch::new(&slope_atlas, (0, -5), 10..=10, 1..=2);
+        let tree_counts = trajectory_search
+            .top(2)
+            .map(|(trajectory, tree_count)| (trajectory.velocity(), tree_count))
+            .collect::<Vec<((i64, i64), usize)>>();
+
+        assert_eq!(tree_counts, [((10, 1), 1), ((10, 2), 2)]);
+    }
+
+    #[test]
+    fn shows_every_trajectory_when_asked_for_more() {
+        let slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+        let trajectory_search = TobogganTrajectorySearch::new(&slope_atlas, (0, 0), 0..=2, 1..=2);
+
+        assert_eq!(trajectory_search.top(100).count(), 6);
+        assert_eq!(trajectory_search.top(4).count(), 4);
+        assert_eq!(trajectory_search.top(0).count(), 0);
+    }
+
+    #[test]
+    fn skips_trajectories_that_never_move_down() {
+        let slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+        let trajectory_search = TobogganTrajectorySearch::new(&slope_atlas, (0, 0), 0..=3, -2..=2);
+        let y_velocities = trajectory_search
+            .top(usize::MAX)
+            .map(|(trajectory, _tree_count)| trajectory.velocity().1)
+            .collect::<Vec<i64>>();
+
+        assert_eq!(y_velocities.len(), 4 * 2);
+        assert!(y_velocities.iter().all(|y_velocity| *y_velocity >= 1));
+
+        let trajectory_search = TobogganTrajectorySearch::new(&slope_atlas, (0, 0), 0..=3, -2..=0);
+        assert!(trajectory_search.best().is_none());
+    }
+}
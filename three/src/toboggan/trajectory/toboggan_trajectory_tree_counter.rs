@@ -0,0 +1,184 @@
+use crate::slope::atlas::{SlopeAtlas, SlopeEdgeMode, SlopeFeature};
+use anyhow::{Error, Result};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// Counts the trees along many trajectories down a wrapping slope at once,
+/// taking advantage of the way the slope repeats.
+///
+/// The toboggan lands on contour `y0 + k * down` at x-position
+/// `x0 + k * right`, so trajectories whose right speeds differ by a multiple
+/// of the breadth of the slope land on the same features. The tree count of
+/// every trajectory follows from the tree counts of the right speeds from `0`
+/// up to the breadth of the slope, which are all worked out in a single pass
+/// over the contours.
+#[derive(Debug)]
+pub struct TobogganTrajectoryTreeCounter {
+    /// How wide the slope is.
+    breadth: i64,
+    /// For every down speed, how many trees fall along the trajectory with
+    /// each right speed from `0` up to `breadth`.
+    tree_counts: HashMap<i64, Vec<usize>>,
+}
+
+impl TobogganTrajectoryTreeCounter {
+    /// Counts the trees along every trajectory down the `slope_atlas` that
+    /// begins at `starting_position` and moves down by one of the
+    /// `y_velocities`, whatever its right speed.
+    ///
+    /// Down speeds less than `1` never reach the bottom of the slope, and are
+    /// skipped. Returns `Err(...)` if the `slope_atlas` does not wrap around
+    /// past its edges, since then it does not repeat.
+    pub fn new(
+        slope_atlas: &SlopeAtlas,
+        starting_position: (i64, i64),
+        y_velocities: RangeInclusive<i64>,
+    ) -> Result<TobogganTrajectoryTreeCounter> {
+        if slope_atlas.edge_mode != SlopeEdgeMode::Wrap {
+            return Err(Error::msg(format!(
+                "Trees can only be counted this way on a wrapping slope, not a {:?} one",
+                slope_atlas.edge_mode
+            )));
+        }
+
+        let breadth = slope_atlas.breadth;
+        let (starting_x_position, starting_y_position) = starting_position;
+        let mut tree_counts = y_velocities
+            .filter(|y_velocity| *y_velocity >= 1)
+            .map(|y_velocity| (y_velocity, vec![0; breadth as usize]))
+            .collect::<HashMap<i64, Vec<usize>>>();
+
+        let mut is_tree_at = Vec::with_capacity(breadth as usize);
+        for y_position in (starting_y_position + 1).max(0)..slope_atlas.height {
+            let descended_distance = y_position - starting_y_position;
+            if !tree_counts
+                .keys()
+                .any(|y_velocity| descended_distance % y_velocity == 0)
+            {
+                continue;
+            }
+
+            is_tree_at.clear();
+            is_tree_at.extend((0..breadth).map(|feature_index| {
+                slope_atlas.feature_at((feature_index, y_position)) == Some(SlopeFeature::Tree)
+            }));
+
+            for (y_velocity, right_speed_tree_counts) in tree_counts.iter_mut() {
+                if descended_distance % y_velocity != 0 {
+                    continue;
+                }
+
+                // Only the number of steps taken modulo the breadth matters, and
+                // each right speed lands that many features past the one before.
+                let number_of_steps = (descended_distance / y_velocity) % breadth;
+                let mut feature_index = starting_x_position.rem_euclid(breadth);
+                for tree_count in right_speed_tree_counts.iter_mut() {
+                    if is_tree_at[feature_index as usize] {
+                        *tree_count += 1;
+                    }
+
+                    feature_index += number_of_steps;
+                    if feature_index >= breadth {
+                        feature_index -= breadth;
+                    }
+                }
+            }
+        }
+
+        Ok(TobogganTrajectoryTreeCounter {
+            breadth,
+            tree_counts,
+        })
+    }
+
+    /// Returns how many trees fall along the trajectory with the given
+    /// `velocity`, or `None` if its down speed was not counted.
+    pub fn number_of_trees(&self, velocity: (i64, i64)) -> Option<usize> {
+        let (x_velocity, y_velocity) = velocity;
+
+        self.tree_counts
+            .get(&y_velocity)
+            .map(|right_speed_tree_counts| {
+                right_speed_tree_counts[x_velocity.rem_euclid(self.breadth) as usize]
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toboggan::trajectory::TobogganTrajectory;
+
+    /// Example slope atlas from https://adventofcode.com/2020/day/3.
+    const EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS: &str = include_str!("../../../files/test.txt");
+
+    /// Narrow slope, so that right speeds wrap around it many times over.
+    const NARROW_SLOPE_ATLAS_FILE_CONTENTS: &str = "#.#\n.##\n#..\n##.\n..#\n#.#\n";
+
+    /// Counts the trees along `trajectory` one position at a time.
+    fn number_of_trees_along_trajectory(
+        slope_atlas: &SlopeAtlas,
+        trajectory: &TobogganTrajectory,
+    ) -> usize {
+        trajectory
+            .descend(slope_atlas)
+            .filter(|position| slope_atlas.feature_at(*position) == Some(SlopeFeature::Tree))
+            .count()
+    }
+
+    #[test]
+    fn agrees_with_each_trajectory_descending_alone() {
+        for slope_atlas_file_contents in [
+            EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS,
+            NARROW_SLOPE_ATLAS_FILE_CONTENTS,
+        ] {
+            let slope_atlas = SlopeAtlas::from_file(slope_atlas_file_contents).unwrap();
+
+            for starting_position in [(0, 0), (2, 1), (-4, 3), (0, -1), (1, -7)] {
+                let tree_counter =
+                    TobogganTrajectoryTreeCounter::new(&slope_atlas, starting_position, 1..=3)
+                        .unwrap();
+
+                for x_velocity in -30..=30 {
+                    for y_velocity in 1..=3 {
+                        let trajectory =
+                            TobogganTrajectory::new(starting_position, (x_velocity, y_velocity));
+
+                        assert_eq!(
+                            tree_counter.number_of_trees((x_velocity, y_velocity)),
+                            Some(number_of_trees_along_trajectory(&slope_atlas, &trajectory)),
+                            "{:?} from {:?} down a slope {} wide",
+                            trajectory.velocity(),
+                            starting_position,
+                            slope_atlas.breadth
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn counts_only_down_speeds_that_reach_the_bottom() {
+        let slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+        let tree_counter =
+            TobogganTrajectoryTreeCounter::new(&slope_atlas, (0, 0), -1..=2).unwrap();
+
+        assert_eq!(tree_counter.number_of_trees((3, 1)), Some(7));
+        assert_eq!(tree_counter.number_of_trees((1, 2)), Some(2));
+        assert_eq!(tree_counter.number_of_trees((3, 0)), None);
+        assert_eq!(tree_counter.number_of_trees((3, -1)), None);
+        assert_eq!(tree_counter.number_of_trees((3, 3)), None);
+    }
+
+    #[test]
+    fn rejects_slopes_that_do_not_wrap() {
+        let mut slope_atlas = SlopeAtlas::from_file(EXAMPLE_SLOPE_ATLAS_FILE_CONTENTS).unwrap();
+
+        for edge_mode in [SlopeEdgeMode::Bounded, SlopeEdgeMode::Reflect] {
+            slope_atlas.edge_mode = edge_mode;
+
+            assert!(TobogganTrajectoryTreeCounter::new(&slope_atlas, (0, 0), 1..=3).is_err());
+        }
+    }
+}
@@ -1,56 +1,622 @@
 extern crate anyhow;
 #[macro_use]
 extern crate lazy_static;
+extern crate rand;
 extern crate regex;
+extern crate serde_json;
 extern crate tokio;
+extern crate unicode_segmentation;
 
 mod password_database;
+mod password_database_format;
+mod password_database_statistics;
+mod password_generator;
+mod password_policy;
+mod password_policy_expression;
+mod password_policy_migration;
 
 use anyhow::{Context, Error, Result};
-use password_database::{PasswordDatabase, PasswordValidationStrategy};
-use std::env::current_dir;
+use password_database::{PasswordDatabase, PasswordDatabaseEntry, PasswordValidationMetadata};
+use password_database_format::PasswordDatabaseFormat;
+use password_database_statistics::PasswordDatabaseStatistics;
+use password_generator::PasswordGenerator;
+use password_policy::{
+    CharacterClass, ForbiddenSubstrings, LetterPositions, LetterRepetitionRange, MinimumLength,
+    PasswordPolicyRegistry, PasswordSegmentation, PasswordValidationStrategy,
+    RequiredCharacterClasses,
+};
+use password_policy_expression::PasswordPolicyExpression;
+use password_policy_migration::PasswordPolicyMigration;
+use std::env::{args, current_dir};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
+/// Password policies written as expressions, equivalent to
+/// `PasswordValidationStrategy::LetterRepetitionRange` and
+/// `PasswordValidationStrategy::LetterPositions` respectively.
+const PASSWORD_POLICY_EXPRESSION_SOURCES: [&str; 2] = [
+    "count(letter) in first..=second",
+    "pos(first) == letter ^ pos(second) == letter",
+];
+
+/// Names of the registered password policies that must judge every generated
+/// password the same way as the `PasswordValidationStrategy` they go with.
+const EQUIVALENT_PASSWORD_POLICY_NAMES: [(PasswordValidationStrategy, [&str; 3]); 2] = [
+    (
+        PasswordValidationStrategy::LetterRepetitionRange,
+        [
+            "repetition range",
+            "grapheme repetition range",
+            "`count(letter) in first..=second`",
+        ],
+    ),
+    (
+        PasswordValidationStrategy::LetterPositions,
+        [
+            "letter position",
+            "grapheme letter position",
+            "`pos(first) == letter ^ pos(second) == letter`",
+        ],
+    ),
+];
+
+/// Largest parameter of the rules generated by the `fuzz` subcommand.
+const MAX_FUZZED_RULE_PARAMETER: i64 = 20;
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let password_database = read_password_database()
+    let is_lenient = args().any(|argument| argument == "--lenient");
+    let is_counter_example = args().any(|argument| argument == "--counter-examples");
+    let seed = match args().find_map(|argument| argument.strip_prefix("--seed=").map(str::to_owned))
+    {
+        Some(seed) => seed
+            .parse::<u64>()
+            .with_context(|| format!("Failed to parse seed \"{}\"", seed))?,
+        None => rand::random::<u64>(),
+    };
+    let positional_arguments = args()
+        .skip(1)
+        .filter(|argument| !argument.starts_with("--"))
+        .collect::<Vec<String>>();
+
+    // Generated passwords have nothing to do with the password database, so
+    // these subcommands are handled before reading it.
+    match positional_arguments.first().map(String::as_str) {
+        Some("generate") => {
+            return print_generated_passwords(&positional_arguments[1..], seed, is_counter_example)
+        }
+        Some("fuzz") => {
+            let number_of_rules = match positional_arguments.get(1) {
+                Some(number_of_rules) => number_of_rules.parse::<usize>().with_context(|| {
+                    format!("Failed to parse number of rules \"{}\"", number_of_rules)
+                })?,
+                None => 1000,
+            };
+
+            return fuzz_password_policies(number_of_rules, seed);
+        }
+        _ => {}
+    }
+
+    let password_database = read_password_database(is_lenient)
         .await
         .context("Failed to read password database")?;
 
-    let number_of_valid_repetition_range_password_entries = password_database
-        .entries
-        .iter()
-        .filter(|password_database_entry| {
-            password_database_entry.is_valid(PasswordValidationStrategy::LetterRepetitionRange)
-        })
-        .count();
+    let password_policy_registry =
+        build_password_policy_registry().context("Failed to build password policies")?;
+
+    match positional_arguments.first().map(String::as_str) {
+        None => print_valid_password_entry_counts(&password_database, &password_policy_registry),
+        Some("report") => print_password_policy_violations(
+            &password_database,
+            &password_policy_registry,
+            positional_arguments.get(1).map(String::as_str),
+        ),
+        Some("migrate") => print_password_policy_migration(
+            &password_database,
+            &password_policy_registry,
+            positional_arguments
+                .get(1)
+                .map_or("repetition range", String::as_str),
+            positional_arguments
+                .get(2)
+                .map_or("letter position", String::as_str),
+        )?,
+        Some("stats") => print_password_database_statistics(&password_database),
+        Some("export") => {
+            let password_database_format = PasswordDatabaseFormat::from_name(
+                positional_arguments.get(1).map_or("text", String::as_str),
+            )?;
+
+            print!("{}", password_database_format.write(&password_database));
+        }
+        Some("convert") => {
+            let source_strategy = PasswordValidationStrategy::from_name(
+                positional_arguments
+                    .get(1)
+                    .context("Missing the strategy to convert from")?,
+            )?;
+            let target_strategy = PasswordValidationStrategy::from_name(
+                positional_arguments
+                    .get(2)
+                    .context("Missing the strategy to convert to")?,
+            )?;
+
+            let (converted_password_database, unconvertible_password_database_entries) =
+                password_database.convert(source_strategy, target_strategy);
+
+            for password_database_entry in unconvertible_password_database_entries {
+                eprintln!(
+                    "line {}: \"{}\" has no equivalent rule, and was left as it is",
+                    password_database_entry.line_number, password_database_entry
+                );
+            }
+
+            print!(
+                "{}",
+                PasswordDatabaseFormat::Text.write(&converted_password_database)
+            );
+        }
+        Some(subcommand) => {
+            return Err(Error::msg(format!(
+                "Unrecognized subcommand \"{}\"",
+                subcommand
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+/// Registers every password policy that the password database is checked
+/// against.
+fn build_password_policy_registry() -> Result<PasswordPolicyRegistry> {
+    let mut password_policy_registry = PasswordPolicyRegistry::new();
+    password_policy_registry
+        .register(
+            "repetition range",
+            PasswordValidationStrategy::LetterRepetitionRange,
+        )
+        .register(
+            "letter position",
+            PasswordValidationStrategy::LetterPositions,
+        )
+        .register(
+            "grapheme repetition range",
+            LetterRepetitionRange {
+                segmentation: PasswordSegmentation::GraphemeClusters,
+            },
+        )
+        .register(
+            "grapheme letter position",
+            LetterPositions {
+                segmentation: PasswordSegmentation::GraphemeClusters,
+            },
+        )
+        .register("minimum length", MinimumLength { min_length: 12 })
+        .register(
+            "character class",
+            RequiredCharacterClasses {
+                character_classes: vec![
+                    CharacterClass::Lowercase,
+                    CharacterClass::Uppercase,
+                    CharacterClass::Digit,
+                    CharacterClass::Symbol,
+                ],
+            },
+        )
+        .register(
+            "forbidden substring",
+            ForbiddenSubstrings {
+                substrings: vec!["password".to_owned(), "1234".to_owned()],
+            },
+        );
+
+    for password_policy_expression_source in PASSWORD_POLICY_EXPRESSION_SOURCES.iter() {
+        let password_policy_expression = PasswordPolicyExpression::parse(
+            password_policy_expression_source,
+        )
+        .map_err(|password_policy_expression_error| {
+            Error::msg(format!(
+                "Failed to parse password policy expression: {}\n{}",
+                password_policy_expression_error,
+                password_policy_expression_error.underline(password_policy_expression_source)
+            ))
+        })?;
+
+        password_policy_registry.register(
+            &format!("`{}`", password_policy_expression_source),
+            password_policy_expression,
+        );
+    }
+
+    Ok(password_policy_registry)
+}
 
+/// Prints how many entries of the `password_database` are valid under each
+/// of the policies in the `password_policy_registry`.
+fn print_valid_password_entry_counts(
+    password_database: &PasswordDatabase,
+    password_policy_registry: &PasswordPolicyRegistry,
+) {
+    for (password_policy_name, number_of_valid_password_entries) in
+        password_policy_registry.count_valid_entries(&password_database.entries)
+    {
+        println!(
+            "Valid {} password entries: {} / {}",
+            password_policy_name,
+            number_of_valid_password_entries,
+            password_database.entries.len()
+        );
+    }
+}
+
+/// Prints every entry of the `password_database` that violates a policy in
+/// the `password_policy_registry`, along with its line number and the reason
+/// why.
+///
+/// Only the policy named `password_policy_name` is checked, if specified.
+fn print_password_policy_violations(
+    password_database: &PasswordDatabase,
+    password_policy_registry: &PasswordPolicyRegistry,
+    password_policy_name: Option<&str>,
+) {
+    let password_policies = password_policy_registry
+        .policies()
+        .filter(|(name, _)| password_policy_name.is_none() || password_policy_name == Some(*name));
+
+    for (name, password_policy) in password_policies {
+        let password_policy_violations = password_database.find_violations(password_policy);
+
+        println!(
+            "Entries violating the {} policy: {} / {}",
+            name,
+            password_policy_violations.len(),
+            password_database.entries.len()
+        );
+
+        for (password_database_entry, password_policy_violation) in password_policy_violations {
+            println!(
+                "\tline {}: \"{}\": {}",
+                password_database_entry.line_number,
+                password_database_entry,
+                password_policy_violation
+            );
+        }
+    }
+}
+
+/// Prints how the entries of the `password_database` are affected by
+/// switching from the policy named `old_password_policy_name` to the policy
+/// named `new_password_policy_name`, both from the `password_policy_registry`.
+fn print_password_policy_migration(
+    password_database: &PasswordDatabase,
+    password_policy_registry: &PasswordPolicyRegistry,
+    old_password_policy_name: &str,
+    new_password_policy_name: &str,
+) -> Result<()> {
+    let find_password_policy = |password_policy_name: &str| {
+        password_policy_registry
+            .get(password_policy_name)
+            .with_context(|| format!("Unrecognized password policy \"{}\"", password_policy_name))
+    };
+    let old_password_policy = find_password_policy(old_password_policy_name)?;
+    let new_password_policy = find_password_policy(new_password_policy_name)?;
+
+    let password_policy_migration = PasswordPolicyMigration::new(
+        &password_database.entries,
+        old_password_policy,
+        new_password_policy,
+    );
+
+    println!(
+        "Migrating from the {} policy to the {} policy:",
+        old_password_policy_name, new_password_policy_name
+    );
+    println!(
+        "\tValid under both:     {}",
+        password_policy_migration.valid_under_both.len()
+    );
     println!(
-        "Valid repetition range password entries: {} / {}",
-        number_of_valid_repetition_range_password_entries,
+        "\tValid under old only: {}",
+        password_policy_migration.valid_under_old_only.len()
+    );
+    println!(
+        "\tValid under new only: {}",
+        password_policy_migration.valid_under_new_only.len()
+    );
+    println!(
+        "\tValid under neither:  {}",
+        password_policy_migration.valid_under_neither.len()
+    );
+    println!(
+        "\tAffected in total:    {} / {}",
+        password_policy_migration.number_of_affected_entries(),
         password_database.entries.len()
     );
 
-    let number_of_valid_letter_positions_password_entries = password_database
+    println!("\nEntries that stop being valid:");
+    for password_database_entry in password_policy_migration.valid_under_old_only.iter() {
+        println!(
+            "\tline {}: \"{}\": {}",
+            password_database_entry.line_number,
+            password_database_entry,
+            password_database_entry
+                .validate(new_password_policy)
+                .err()
+                .map(|password_policy_violation| password_policy_violation.to_string())
+                .unwrap_or_default()
+        );
+    }
+
+    println!("\nEntries that become valid:");
+    for password_database_entry in password_policy_migration.valid_under_new_only.iter() {
+        println!(
+            "\tline {}: \"{}\": {}",
+            password_database_entry.line_number,
+            password_database_entry,
+            password_database_entry
+                .validate(old_password_policy)
+                .err()
+                .map(|password_policy_violation| password_policy_violation.to_string())
+                .unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints statistics about the entries of the `password_database`, including
+/// every rule that can never be satisfied.
+fn print_password_database_statistics(password_database: &PasswordDatabase) {
+    let password_database_statistics = PasswordDatabaseStatistics::new(&password_database.entries);
+    let number_of_entries = password_database.entries.len();
+    let percentage_of_entries =
+        |count: usize| 100.0 * count as f64 / number_of_entries.max(1) as f64;
+
+    println!("Password lengths:");
+    for (password_length, count) in password_database_statistics.password_lengths.iter() {
+        println!(
+            "\t{:>4}: {:>5} ({:.1}%)",
+            password_length,
+            count,
+            percentage_of_entries(*count)
+        );
+    }
+
+    println!("\nLetter frequencies:");
+    for (letter, count) in password_database_statistics.letter_frequencies.iter() {
+        println!(
+            "\t{:>4}: {:>5} ({:.1}%)",
+            letter,
+            count,
+            percentage_of_entries(*count)
+        );
+    }
+
+    println!("\nParameter ranges (second - first):");
+    for (parameter_range, count) in password_database_statistics.parameter_ranges.iter() {
+        println!(
+            "\t{:>4}: {:>5} ({:.1}%)",
+            parameter_range,
+            count,
+            percentage_of_entries(*count)
+        );
+    }
+
+    println!(
+        "\nUnsatisfiable rules: {}",
+        password_database_statistics.unsatisfiable_rules.len()
+    );
+    for (password_database_entry, strategy, reason) in
+        password_database_statistics.unsatisfiable_rules.iter()
+    {
+        println!(
+            "\tline {}: \"{}\": under {}, {}",
+            password_database_entry.line_number, password_database_entry, strategy, reason
+        );
+    }
+}
+
+/// Prints a password database of passwords that comply with the rule described
+/// by `arguments`, or that just barely fail to if `is_counter_example` is
+/// `true`.
+///
+/// `arguments` are the strategy name, the `first-second` parameters, the
+/// letter and, optionally, how many passwords to generate.
+fn print_generated_passwords(
+    arguments: &[String],
+    seed: u64,
+    is_counter_example: bool,
+) -> Result<()> {
+    let strategy = PasswordValidationStrategy::from_name(
+        arguments
+            .first()
+            .context("Missing the strategy to generate passwords for")?,
+    )?;
+    let parameters_text = arguments
+        .get(1)
+        .context("Missing the parameters to generate passwords for")?;
+    let letter = arguments
+        .get(2)
+        .context("Missing the letter to generate passwords for")?;
+    let number_of_passwords = match arguments.get(3) {
+        Some(number_of_passwords) => number_of_passwords.parse::<usize>().with_context(|| {
+            format!(
+                "Failed to parse number of passwords \"{}\"",
+                number_of_passwords
+            )
+        })?,
+        None => 10,
+    };
+
+    let parse_parameter = |parameter_text: &str| {
+        parameter_text
+            .parse::<i64>()
+            .with_context(|| format!("Failed to parse parameters \"{}\"", parameters_text))
+    };
+    let (first_parameter_text, second_parameter_text) =
+        parameters_text.split_once('-').with_context(|| {
+            format!(
+                "Parameters \"{}\" are not of the form `first-second`",
+                parameters_text
+            )
+        })?;
+    let rule = PasswordValidationMetadata {
+        letter: letter.to_owned(),
+        parameters: (
+            parse_parameter(first_parameter_text)?,
+            parse_parameter(second_parameter_text)?,
+        ),
+    };
+
+    eprintln!("Generating passwords with seed {}", seed);
+    let mut password_generator = PasswordGenerator::from_seed(seed);
+
+    let mut entries = vec![];
+    for line_index in 0..number_of_passwords {
+        let password = if is_counter_example {
+            password_generator.generate_counter_example(&rule, strategy)
+        } else {
+            password_generator.generate(&rule, strategy)
+        }
+        .with_context(|| {
+            format!(
+                "No password of reasonable length {} this rule",
+                if is_counter_example {
+                    "just barely fails"
+                } else {
+                    "complies with"
+                }
+            )
+        })?;
+
+        entries.push(PasswordDatabaseEntry {
+            line_number: line_index + 1,
+            password,
+            validation_metadata: rule.clone(),
+        });
+    }
+
+    print!(
+        "{}",
+        PasswordDatabaseFormat::Text.write(&PasswordDatabase { entries })
+    );
+
+    Ok(())
+}
+
+/// Generates `number_of_rules` random rules, along with a compliant password
+/// and a counter-example for each, and prints every generated entry that the
+/// policies in `EQUIVALENT_PASSWORD_POLICY_NAMES` misjudge, or that does not
+/// read back in unchanged through `PasswordDatabase::from_file(...)`.
+fn fuzz_password_policies(number_of_rules: usize, seed: u64) -> Result<()> {
+    let password_policy_registry =
+        build_password_policy_registry().context("Failed to build password policies")?;
+
+    eprintln!("Fuzzing password policies with seed {}", seed);
+    let mut password_generator = PasswordGenerator::from_seed(seed);
+
+    let mut generated_entries = vec![];
+    let mut number_of_misjudged_entries = 0;
+    for _ in 0..number_of_rules {
+        let rule = password_generator.generate_rule(MAX_FUZZED_RULE_PARAMETER);
+
+        for (strategy, password_policy_names) in EQUIVALENT_PASSWORD_POLICY_NAMES.iter() {
+            let generated_passwords = [
+                (true, password_generator.generate(&rule, *strategy)),
+                (
+                    false,
+                    password_generator.generate_counter_example(&rule, *strategy),
+                ),
+            ];
+
+            for (is_expected_valid, password) in generated_passwords.iter() {
+                let password = match password {
+                    Some(password) => password,
+                    None => continue,
+                };
+                let entry = PasswordDatabaseEntry {
+                    line_number: generated_entries.len() + 1,
+                    password: password.to_owned(),
+                    validation_metadata: rule.clone(),
+                };
+
+                for password_policy_name in password_policy_names.iter() {
+                    let password_policy = password_policy_registry
+                        .get(password_policy_name)
+                        .with_context(|| {
+                            format!("Unrecognized password policy \"{}\"", password_policy_name)
+                        })?;
+
+                    if password_policy.is_valid(&entry) != *is_expected_valid {
+                        number_of_misjudged_entries += 1;
+                        println!(
+                            "\t\"{}\" should be {} under the {} policy",
+                            entry,
+                            if *is_expected_valid {
+                                "valid"
+                            } else {
+                                "invalid"
+                            },
+                            password_policy_name
+                        );
+                    }
+                }
+
+                generated_entries.push(entry);
+            }
+        }
+    }
+
+    // Everything generated has to survive being written out and read back in,
+    // or it is no use as a fixture.
+    let generated_password_database = PasswordDatabase {
+        entries: generated_entries,
+    };
+    let (read_password_database, password_database_parse_errors) =
+        PasswordDatabase::from_file_leniently(
+            &PasswordDatabaseFormat::Text.write(&generated_password_database),
+        );
+    for password_database_parse_error in password_database_parse_errors.iter() {
+        println!("\t{}", password_database_parse_error);
+    }
+    let altered_entries = read_password_database
         .entries
         .iter()
-        .filter(|password_database_entry| {
-            password_database_entry.is_valid(PasswordValidationStrategy::LetterPositions)
+        .filter(|read_entry| {
+            generated_password_database
+                .entries
+                .get(read_entry.line_number - 1)
+                != Some(read_entry)
         })
-        .count();
+        .collect::<Vec<&PasswordDatabaseEntry>>();
+    for altered_entry in altered_entries.iter() {
+        println!(
+            "\t\"{}\" on line {} was altered by being read back in",
+            altered_entry, altered_entry.line_number
+        );
+    }
 
     println!(
-        "Valid letter position password entries: {} / {}",
-        number_of_valid_letter_positions_password_entries,
-        password_database.entries.len()
+        "Misjudged generated password entries: {} / {}",
+        number_of_misjudged_entries,
+        generated_password_database.entries.len()
+    );
+    println!(
+        "Unreadable generated password entries: {} / {}",
+        password_database_parse_errors.len() + altered_entries.len(),
+        generated_password_database.entries.len()
     );
 
     Ok(())
 }
 
 /// Turns input file into a new instance of `PasswordDatabase`.
-async fn read_password_database() -> Result<PasswordDatabase, Error> {
+///
+/// If `is_lenient` is `true`, malformed lines are reported and skipped rather
+/// than failing the whole file.
+async fn read_password_database(is_lenient: bool) -> Result<PasswordDatabase, Error> {
     let pwd = current_dir().context("Failed to read current working directory")?;
     let password_database_file_path = pwd.join("files/input.txt");
 
@@ -77,5 +643,23 @@ async fn read_password_database() -> Result<PasswordDatabase, Error> {
     let password_database_file_contents =
         String::from_utf8_lossy(&raw_password_database_file_contents);
 
-    PasswordDatabase::from_file(&password_database_file_contents)
+    if !is_lenient {
+        return PasswordDatabase::from_file(&password_database_file_contents);
+    }
+
+    let (password_database, password_database_parse_errors) =
+        PasswordDatabase::from_file_leniently(&password_database_file_contents);
+
+    if !password_database_parse_errors.is_empty() {
+        eprintln!(
+            "Skipped {} malformed password database entries:",
+            password_database_parse_errors.len()
+        );
+
+        for password_database_parse_error in password_database_parse_errors {
+            eprintln!("\t{}", password_database_parse_error);
+        }
+    }
+
+    Ok(password_database)
 }
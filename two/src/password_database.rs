@@ -1,5 +1,10 @@
-use anyhow::{Context, Error, Result};
+use crate::password_policy::{
+    PasswordPolicy, PasswordPolicyViolation, PasswordSegmentation, PasswordValidationStrategy,
+};
+use anyhow::{Context, Result};
 use regex::Regex;
+use std::fmt::{Display, Formatter};
+use unicode_segmentation::UnicodeSegmentation;
 
 lazy_static! {
     /// Matches password database entries.
@@ -11,12 +16,12 @@ lazy_static! {
     /// | repeated letter  | 3     |
     /// | password         | 4     |
     static ref PASSWORD_DATABASE_ENTRY_PATTERN: Regex =
-        Regex::new(r"^(\d+)-(\d+)\s+([a-z]):\s+([a-z]+)$").unwrap();
+        Regex::new(r"^([0-9]+)-([0-9]+)\s+(\S+?):\s+(\S+)$").unwrap();
 }
 
 /// Stores password information for the North Pole Toboggan Rental Shop
 /// computer system.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct PasswordDatabase {
     /// Password wrapped by this `PasswordDatabaseEntry`.
     pub entries: Vec<PasswordDatabaseEntry>,
@@ -28,123 +33,454 @@ impl PasswordDatabase {
     ///
     /// For more on the format of `password_database_file_contents`,
     /// see https://adventofcode.com/2020/day/2.
+    ///
+    /// Returns `Err(...)` describing the first malformed line, if any.
     pub fn from_file(password_database_file_contents: &str) -> Result<PasswordDatabase> {
         let entries = password_database_file_contents
             .lines()
-            .map(|line| PasswordDatabaseEntry::from_text(line))
-            .collect::<Result<Vec<PasswordDatabaseEntry>>>()?;
+            .enumerate()
+            .map(|(line_index, line)| PasswordDatabaseEntry::from_text(line_index + 1, line))
+            .collect::<Result<Vec<PasswordDatabaseEntry>, PasswordDatabaseParseError>>()
+            .context("Failed to parse password database entry")?;
 
         Ok(PasswordDatabase { entries })
     }
+
+    /// Constructs a new `PasswordDatabase` out of every well-formed line of
+    /// the provided `password_database_file_contents`, skipping over the rest.
+    ///
+    /// Every malformed line is returned alongside the database, in order of
+    /// appearance.
+    pub fn from_file_leniently(
+        password_database_file_contents: &str,
+    ) -> (PasswordDatabase, Vec<PasswordDatabaseParseError>) {
+        let mut entries = vec![];
+        let mut parse_errors = vec![];
+
+        for (line_index, line) in password_database_file_contents.lines().enumerate() {
+            match PasswordDatabaseEntry::from_text(line_index + 1, line) {
+                Ok(entry) => entries.push(entry),
+                Err(parse_error) => parse_errors.push(parse_error),
+            }
+        }
+
+        (PasswordDatabase { entries }, parse_errors)
+    }
+
+    /// Rewrites the rule of every entry, as interpreted by `source_strategy`,
+    /// into an equivalent rule as interpreted by `target_strategy`.
+    ///
+    /// Entries for which no equivalent rule exists are left as they are, and
+    /// are also returned separately. See `PasswordDatabaseEntry::convert(...)`
+    /// for what counts as equivalent.
+    pub fn convert(
+        &self,
+        source_strategy: PasswordValidationStrategy,
+        target_strategy: PasswordValidationStrategy,
+    ) -> (PasswordDatabase, Vec<&PasswordDatabaseEntry>) {
+        let mut entries = vec![];
+        let mut unconvertible_entries = vec![];
+
+        for entry in self.entries.iter() {
+            match entry.convert(source_strategy, target_strategy) {
+                Some(converted_entry) => entries.push(converted_entry),
+                None => {
+                    entries.push(entry.clone());
+                    unconvertible_entries.push(entry);
+                }
+            }
+        }
+
+        (PasswordDatabase { entries }, unconvertible_entries)
+    }
+
+    /// Lists every entry of this database that violates `policy`, along with
+    /// the reason why, in order of appearance.
+    pub fn find_violations<'a, P>(
+        &'a self,
+        policy: &P,
+    ) -> Vec<(&'a PasswordDatabaseEntry, PasswordPolicyViolation)>
+    where
+        P: PasswordPolicy + ?Sized,
+    {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .validate(policy)
+                    .err()
+                    .map(|violation| (entry, violation))
+            })
+            .collect()
+    }
 }
 
 /// A single entry of the corporate password database.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PasswordDatabaseEntry {
+    /// One-based number of the line of the password database file holding
+    /// this entry.
+    pub line_number: usize,
     /// Password wrapped by this `PasswordDatabaseEntry`.
-    password: String,
+    pub password: String,
     /// Data used to validate `password`.
-    validation_metadata: PasswordValidationMetadata,
+    pub validation_metadata: PasswordValidationMetadata,
 }
 
 impl PasswordDatabaseEntry {
-    /// Constructs a new `PasswordDatabaseEntry` from the provided `text`.
+    /// Constructs a new `PasswordDatabaseEntry` from the provided `text`,
+    /// found at `line_number` of the password database file.
     ///
     /// For more on the format of `text`,
     /// see https://adventofcode.com/2020/day/2.
-    fn from_text(text: &str) -> Result<PasswordDatabaseEntry, Error> {
+    fn from_text(
+        line_number: usize,
+        text: &str,
+    ) -> Result<PasswordDatabaseEntry, PasswordDatabaseParseError> {
+        let parse_error = |problem: String| PasswordDatabaseParseError {
+            line_number,
+            text: text.to_owned(),
+            problem,
+        };
+
         let capture_groups = PASSWORD_DATABASE_ENTRY_PATTERN
             .captures(text)
-            .with_context(|| format!("Failed to parse password database entry text: {}", text))?;
-
-        let first_parameter = capture_groups.get(1).unwrap().as_str().parse::<i64>()?;
-        let second_parameter = capture_groups.get(2).unwrap().as_str().parse::<i64>()?;
-        let letter = capture_groups
-            .get(3)
-            .unwrap()
-            .as_str()
-            .chars()
-            .next()
-            .unwrap();
+            .ok_or_else(|| parse_error(diagnose_malformed_entry_text(text)))?;
+
+        let parse_parameter = |capture_group_index: usize| {
+            let parameter_text = capture_groups.get(capture_group_index).unwrap().as_str();
+
+            parameter_text.parse::<i64>().map_err(|_| {
+                parse_error(format!(
+                    "bad range: `{}` is too large to be a repetition count or position",
+                    parameter_text
+                ))
+            })
+        };
+
+        let first_parameter = parse_parameter(1)?;
+        let second_parameter = parse_parameter(2)?;
+        let letter = capture_groups.get(3).unwrap().as_str();
+        if letter.graphemes(true).count() != 1 {
+            return Err(parse_error(format!(
+                "letter `{}` must be a single character",
+                letter
+            )));
+        }
+
         let password = capture_groups.get(4).unwrap().as_str();
 
         Ok(PasswordDatabaseEntry {
+            line_number,
             password: password.to_owned(),
             validation_metadata: PasswordValidationMetadata {
-                letter,
+                letter: letter.to_owned(),
                 parameters: (first_parameter, second_parameter),
             },
         })
     }
 
-    /// Returns `true` if this entry's `rule` applies to its `password`.
-    pub fn is_valid(&self, strategy: PasswordValidationStrategy) -> bool {
-        match strategy {
-            PasswordValidationStrategy::LetterRepetitionRange => {
-                let PasswordDatabaseEntry {
-                    password,
-                    validation_metadata:
-                        PasswordValidationMetadata {
-                            letter,
-                            parameters: (min_repetitions, max_repetitions),
-                        },
-                } = self;
-
-                let repetitions = password
-                    .chars()
-                    .filter(|password_char| *password_char == *letter)
-                    .count() as i64;
-
-                repetitions >= *min_repetitions && repetitions <= *max_repetitions
+    /// Returns `true` if this entry's password complies with `policy`.
+    pub fn is_valid<P>(&self, policy: &P) -> bool
+    where
+        P: PasswordPolicy + ?Sized,
+    {
+        policy.is_valid(self)
+    }
+
+    /// Returns `Err(...)` explaining why if this entry's password does not
+    /// comply with `policy`.
+    pub fn validate<P>(&self, policy: &P) -> Result<(), PasswordPolicyViolation>
+    where
+        P: PasswordPolicy + ?Sized,
+    {
+        policy.validate(self)
+    }
+
+    /// Returns a copy of this entry with its rule, as interpreted by
+    /// `source_strategy`, rewritten into an equivalent rule as interpreted by
+    /// `target_strategy`.
+    ///
+    /// Rules are equivalent if they judge the entry's password to be valid or
+    /// invalid alike. This only holds for the entry's own password: the
+    /// rewritten rule is made up to give the same verdict for it, and may
+    /// well judge other passwords differently than the original rule does.
+    /// The rule is left as it is if `source_strategy` and `target_strategy`
+    /// are the same.
+    ///
+    /// Returns `None` if there is no such rule, which happens when a password
+    /// that does not contain its letter at all has to be valid under
+    /// `PasswordValidationStrategy::LetterPositions`.
+    pub fn convert(
+        &self,
+        source_strategy: PasswordValidationStrategy,
+        target_strategy: PasswordValidationStrategy,
+    ) -> Option<PasswordDatabaseEntry> {
+        if source_strategy == target_strategy {
+            return Some(self.clone());
+        }
+
+        let is_valid = self.is_valid(&source_strategy);
+        let letter = self.validation_metadata.letter.as_str();
+        let units = PasswordSegmentation::CodePoints.segment(&self.password);
+
+        // Positions past the end of the password never hold the letter, so
+        // they are tacked on to make sure there are always enough positions
+        // without it.
+        let (letter_positions, other_positions): (Vec<i64>, Vec<i64>) = (1..=(units.len() as i64
+            + 2))
+            .partition(|position| units.get((*position - 1) as usize) == Some(&letter));
+
+        let parameters = match (target_strategy, is_valid) {
+            (PasswordValidationStrategy::LetterRepetitionRange, true) => {
+                (letter_positions.len() as i64, letter_positions.len() as i64)
             }
-            PasswordValidationStrategy::LetterPositions => {
-                let PasswordDatabaseEntry {
-                    password,
-                    validation_metadata:
-                        PasswordValidationMetadata {
-                            letter,
-                            parameters: (first_position, second_position),
-                        },
-                } = self;
-
-                let does_first_position_match = does_letter_match(letter, password, first_position);
-                let does_second_position_match =
-                    does_letter_match(letter, password, second_position);
-
-                (does_first_position_match || does_second_position_match)
-                    && !(does_first_position_match && does_second_position_match)
+            (PasswordValidationStrategy::LetterRepetitionRange, false) => (
+                letter_positions.len() as i64 + 1,
+                letter_positions.len() as i64 + 1,
+            ),
+            (PasswordValidationStrategy::LetterPositions, true) => {
+                let letter_position = *letter_positions.first()?;
+                let other_position = other_positions[0];
+
+                (
+                    letter_position.min(other_position),
+                    letter_position.max(other_position),
+                )
             }
-        }
+            (PasswordValidationStrategy::LetterPositions, false) => {
+                if letter_positions.len() >= 2 {
+                    (letter_positions[0], letter_positions[1])
+                } else {
+                    (other_positions[0], other_positions[1])
+                }
+            }
+        };
+
+        Some(PasswordDatabaseEntry {
+            line_number: self.line_number,
+            password: self.password.clone(),
+            validation_metadata: PasswordValidationMetadata {
+                letter: self.validation_metadata.letter.clone(),
+                parameters,
+            },
+        })
+    }
+}
+
+impl Display for PasswordDatabaseEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{} {}: {}",
+            self.validation_metadata.parameters.0,
+            self.validation_metadata.parameters.1,
+            self.validation_metadata.letter,
+            self.password
+        )
     }
 }
 
-/// Enumerates every way that `PasswordValidationMetadata` can be used.
-pub enum PasswordValidationStrategy {
-    /// Interprets `PasswordValidationMetadata#parameters` as a repetition
-    /// range where the first value is the (inclusive) minimum number of
-    /// letter repetitions in a valid password and the second value is the
-    /// (inclusive) maximum number of repetitions in a valid password.
-    LetterRepetitionRange,
-    /// Interprets `PasswordValidationMetadata#parameters` as legitimate
-    /// positions for the letter in a valid password.
-    LetterPositions,
+/// Explains why a line of a password database file could not be parsed into a
+/// `PasswordDatabaseEntry`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordDatabaseParseError {
+    /// One-based number of the offending line.
+    pub line_number: usize,
+    /// Text of the offending line.
+    pub text: String,
+    /// Human-readable description of what is wrong with `text`.
+    pub problem: String,
 }
 
+impl Display for PasswordDatabaseParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {} (\"{}\"): {}",
+            self.line_number, self.text, self.problem
+        )
+    }
+}
+
+impl std::error::Error for PasswordDatabaseParseError {}
+
 /// Describes a valid password.
-#[derive(Debug)]
-struct PasswordValidationMetadata {
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordValidationMetadata {
     /// The letter to which `maxRepetitions` and `minRepetitions` refer.
-    letter: char,
+    ///
+    /// Always a single grapheme cluster, which may be made up of several code
+    /// points.
+    pub letter: String,
     /// Values configuring validation.
-    parameters: (i64, i64),
+    pub parameters: (i64, i64),
+}
+
+/// Works out what is wrong with `text`, a line of a password database file
+/// that does not match `PASSWORD_DATABASE_ENTRY_PATTERN`.
+fn diagnose_malformed_entry_text(text: &str) -> String {
+    let colon_index = match text.find(':') {
+        Some(colon_index) => colon_index,
+        None => return "missing colon between the rule and the password".to_owned(),
+    };
+
+    let rule = &text[..colon_index];
+    let rule_parts = rule.split_whitespace().collect::<Vec<&str>>();
+    let (range, letter) = match rule_parts.as_slice() {
+        [] => return "missing rule before the colon".to_owned(),
+        [range] if rule.ends_with(char::is_whitespace) || range.contains('-') => {
+            return "missing letter between the range and the colon".to_owned()
+        }
+        [_] => return "missing range before the letter".to_owned(),
+        [range, letter] => (*range, *letter),
+        _ => return format!("unexpected text in rule `{}`", rule.trim()),
+    };
+
+    let is_parameter = |parameter: &str| {
+        !parameter.is_empty()
+            && parameter
+                .chars()
+                .all(|parameter_char| parameter_char.is_ascii_digit())
+    };
+    match range.split_once('-') {
+        Some((min, max)) if is_parameter(min) && is_parameter(max) => {}
+        _ => return format!("bad range: `{}` is not of the form `min-max`", range),
+    }
+
+    if rule.ends_with(char::is_whitespace) {
+        return "unexpected whitespace between the letter and the colon".to_owned();
+    }
+
+    if letter.graphemes(true).count() != 1 {
+        return format!("letter `{}` must be a single character", letter);
+    }
+
+    let password_part = &text[(colon_index + 1)..];
+    let password = password_part.trim();
+    if password.is_empty() {
+        return "missing password after the colon".to_owned();
+    }
+    if !password_part.starts_with(char::is_whitespace) {
+        return "missing whitespace between the colon and the password".to_owned();
+    }
+
+    if password.contains(char::is_whitespace) {
+        return "password contains whitespace".to_owned();
+    }
+
+    "entry does not match the format `min-max letter: password`".to_owned()
 }
 
-/// Returns `true` if the `password` character at the specified `position` matches
-/// `letter`.
-fn does_letter_match(letter: &char, password: &str, position: &i64) -> bool {
-    password
-        .chars()
-        .nth((position - 1) as usize)
-        .map(|password_char| password_char == *letter)
-        .unwrap_or(false)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_problem_with_every_malformed_line() {
+        let malformed_lines = [
+            (
+                "1-3 a abcde",
+                "missing colon between the rule and the password",
+            ),
+            (": abcde", "missing rule before the colon"),
+            ("a: abcde", "missing range before the letter"),
+            (
+                "1-3 : abcde",
+                "missing letter between the range and the colon",
+            ),
+            (
+                "1-3 a b: abcde",
+                "unexpected text in rule `1-3 a b`",
+            ),
+            (
+                "1_3 a: abcde",
+                "bad range: `1_3` is not of the form `min-max`",
+            ),
+            (
+                "1-99999999999999999999 a: abcde",
+                "bad range: `99999999999999999999` is too large to be a repetition count or position",
+            ),
+            (
+                "1-3 a : abcde",
+                "unexpected whitespace between the letter and the colon",
+            ),
+            (
+                "1-3 ab: abcde",
+                "letter `ab` must be a single character",
+            ),
+            ("1-3 a: ", "missing password after the colon"),
+            ("1-3 a:", "missing password after the colon"),
+            (
+                "1-3 a:abcde",
+                "missing whitespace between the colon and the password",
+            ),
+            ("1-3 a: ab cde", "password contains whitespace"),
+        ];
+        let mut password_database_file_contents = "1-3 a: abcde\n".to_owned();
+        for (malformed_line, _problem) in malformed_lines.iter() {
+            password_database_file_contents += &format!("{}\n2-9 c: ccccccccc\n", malformed_line);
+        }
+
+        let (password_database, parse_errors) =
+            PasswordDatabase::from_file_leniently(&password_database_file_contents);
+
+        assert_eq!(password_database.entries.len(), malformed_lines.len() + 1);
+        assert_eq!(password_database.entries[0].password, "abcde");
+        for (entry_index, entry) in password_database.entries.iter().enumerate().skip(1) {
+            assert_eq!(entry.line_number, 2 * entry_index + 1);
+            assert_eq!(entry.password, "ccccccccc");
+        }
+
+        assert_eq!(parse_errors.len(), malformed_lines.len());
+        for (line_index, (parse_error, (malformed_line, problem))) in
+            parse_errors.iter().zip(malformed_lines.iter()).enumerate()
+        {
+            assert_eq!(
+                *parse_error,
+                PasswordDatabaseParseError {
+                    line_number: 2 * line_index + 2,
+                    text: malformed_line.to_string(),
+                    problem: problem.to_string(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_grapheme_cluster_letter() {
+        let entry = PasswordDatabaseEntry::from_text(1, "1-3 👩\u{200d}👧: 👩\u{200d}👧x").unwrap();
+
+        assert_eq!(entry.validation_metadata.letter, "👩\u{200d}👧");
+        assert_eq!(entry.password, "👩\u{200d}👧x");
+
+        let entry = PasswordDatabaseEntry::from_text(1, "1-3 e\u{301}: xe\u{301}").unwrap();
+
+        assert_eq!(entry.validation_metadata.letter, "e\u{301}");
+    }
+
+    #[test]
+    fn rejects_letter_of_several_grapheme_clusters() {
+        for text in ["1-3 ab: abc", "1-3 e\u{301}e: abc", "1-3 👩👧: abc"] {
+            let parse_error = PasswordDatabaseEntry::from_text(1, text).unwrap_err();
+
+            assert!(
+                parse_error.problem.contains("must be a single character"),
+                "{}",
+                parse_error
+            );
+        }
+    }
+
+    #[test]
+    fn convert_keeps_rule_between_same_strategies() {
+        let entry = PasswordDatabaseEntry::from_text(1, "1-3 a: xax").unwrap();
+
+        for strategy in [
+            PasswordValidationStrategy::LetterRepetitionRange,
+            PasswordValidationStrategy::LetterPositions,
+        ] {
+            assert_eq!(entry.convert(strategy, strategy), Some(entry.clone()));
+        }
+    }
 }
@@ -0,0 +1,155 @@
+use crate::password_database::PasswordDatabase;
+use anyhow::{Error, Result};
+use serde_json::json;
+
+/// Enumerates every format that a `PasswordDatabase` can be written out in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PasswordDatabaseFormat {
+    /// The original `min-max letter: password` format, one entry per line,
+    /// which `PasswordDatabase::from_file(...)` reads back in.
+    ///
+    /// Line numbers are not written out, so entries read back in are
+    /// numbered by where they now appear. That only differs from the
+    /// original numbering if some lines were skipped, as with
+    /// `PasswordDatabase::from_file_leniently(...)`.
+    Text,
+    /// A JSON array with one object per entry.
+    Json,
+    /// Comma-separated values with a header row, quoted as described by
+    /// [RFC 4180](https://tools.ietf.org/html/rfc4180).
+    Csv,
+}
+
+impl PasswordDatabaseFormat {
+    /// Returns the `PasswordDatabaseFormat` named by `name`.
+    pub fn from_name(name: &str) -> Result<PasswordDatabaseFormat> {
+        match name {
+            "text" => Ok(PasswordDatabaseFormat::Text),
+            "json" => Ok(PasswordDatabaseFormat::Json),
+            "csv" => Ok(PasswordDatabaseFormat::Csv),
+            _ => Err(Error::msg(format!(
+                "Unrecognized password database format \"{}\"",
+                name
+            ))),
+        }
+    }
+
+    /// Writes out every entry of `password_database` in this format.
+    pub fn write(&self, password_database: &PasswordDatabase) -> String {
+        match self {
+            PasswordDatabaseFormat::Text => password_database
+                .entries
+                .iter()
+                .map(|entry| format!("{}\n", entry))
+                .collect(),
+            PasswordDatabaseFormat::Json => {
+                let json_entries = password_database
+                    .entries
+                    .iter()
+                    .map(|entry| {
+                        json!({
+                            "line_number": entry.line_number,
+                            "parameters": [
+                                entry.validation_metadata.parameters.0,
+                                entry.validation_metadata.parameters.1,
+                            ],
+                            "letter": entry.validation_metadata.letter,
+                            "password": entry.password,
+                        })
+                    })
+                    .collect::<Vec<serde_json::Value>>();
+
+                format!("{:#}\n", serde_json::Value::Array(json_entries))
+            }
+            PasswordDatabaseFormat::Csv => {
+                let mut csv =
+                    "line_number,first_parameter,second_parameter,letter,password\n".to_owned();
+
+                for entry in password_database.entries.iter() {
+                    csv += &format!(
+                        "{},{},{},{},{}\n",
+                        entry.line_number,
+                        entry.validation_metadata.parameters.0,
+                        entry.validation_metadata.parameters.1,
+                        quote_csv_field(&entry.validation_metadata.letter),
+                        quote_csv_field(&entry.password)
+                    );
+                }
+
+                csv
+            }
+        }
+    }
+}
+
+/// Wraps `field` in double quotes if it contains anything that would
+/// otherwise be mistaken for CSV syntax.
+fn quote_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Password database file with entries that stretch the text format:
+    /// Unicode passwords and letters, and a letter that is a colon itself.
+    const UNUSUAL_PASSWORD_DATABASE: &str = "\
+1-3 a: abcde
+2-9 é: éléphant
+1-2 e\u{301}: cafe\u{301}
+0-4 👩\u{200d}👧: 👩\u{200d}👧🎄ünïcödé
+1-3 :: a:b::c
+5-5 \": \"quoted\",comma
+";
+
+    #[test]
+    fn text_round_trips_through_from_file() {
+        let password_database = PasswordDatabase::from_file(UNUSUAL_PASSWORD_DATABASE).unwrap();
+        let password_database_text = PasswordDatabaseFormat::Text.write(&password_database);
+
+        assert_eq!(password_database_text, UNUSUAL_PASSWORD_DATABASE);
+        assert_eq!(
+            PasswordDatabase::from_file(&password_database_text).unwrap(),
+            password_database
+        );
+    }
+
+    #[test]
+    fn text_renumbers_entries_skipped_over_leniently() {
+        let (password_database, parse_errors) =
+            PasswordDatabase::from_file_leniently("1-3 a: abcde\nnot an entry\n1-3 b: cdefg\n");
+        let password_database_text = PasswordDatabaseFormat::Text.write(&password_database);
+        let read_password_database = PasswordDatabase::from_file(&password_database_text).unwrap();
+
+        assert_eq!(parse_errors.len(), 1);
+        assert_eq!(
+            password_database
+                .entries
+                .iter()
+                .map(|entry| entry.line_number)
+                .collect::<Vec<usize>>(),
+            [1, 3]
+        );
+        assert_eq!(
+            read_password_database
+                .entries
+                .iter()
+                .map(|entry| entry.line_number)
+                .collect::<Vec<usize>>(),
+            [1, 2]
+        );
+        for (entry, read_entry) in password_database
+            .entries
+            .iter()
+            .zip(read_password_database.entries.iter())
+        {
+            assert_eq!(entry.password, read_entry.password);
+            assert_eq!(entry.validation_metadata, read_entry.validation_metadata);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::password_database::PasswordDatabaseEntry;
+use crate::password_policy::{PasswordSegmentation, PasswordValidationStrategy};
+use std::collections::BTreeMap;
+
+/// Summarizes the entries of a password database.
+#[derive(Debug)]
+pub struct PasswordDatabaseStatistics<'a> {
+    /// Number of passwords of each length, in code points.
+    pub password_lengths: BTreeMap<usize, usize>,
+    /// Number of rules naming each letter.
+    pub letter_frequencies: BTreeMap<&'a str, usize>,
+    /// Number of rules whose second parameter exceeds the first by each
+    /// amount.
+    pub parameter_ranges: BTreeMap<i64, usize>,
+    /// Every entry whose rule can never be satisfied under a
+    /// `PasswordValidationStrategy`, along with that strategy and the reason
+    /// why, in order of appearance.
+    pub unsatisfiable_rules: Vec<(
+        &'a PasswordDatabaseEntry,
+        PasswordValidationStrategy,
+        String,
+    )>,
+}
+
+impl<'a> PasswordDatabaseStatistics<'a> {
+    /// Gathers statistics about the `entries`.
+    pub fn new(entries: &'a [PasswordDatabaseEntry]) -> PasswordDatabaseStatistics<'a> {
+        let mut statistics = PasswordDatabaseStatistics {
+            password_lengths: BTreeMap::new(),
+            letter_frequencies: BTreeMap::new(),
+            parameter_ranges: BTreeMap::new(),
+            unsatisfiable_rules: vec![],
+        };
+
+        for entry in entries {
+            let password_length = PasswordSegmentation::CodePoints
+                .segment(&entry.password)
+                .len();
+            let (first_parameter, second_parameter) = entry.validation_metadata.parameters;
+
+            *statistics
+                .password_lengths
+                .entry(password_length)
+                .or_insert(0) += 1;
+            *statistics
+                .letter_frequencies
+                .entry(entry.validation_metadata.letter.as_str())
+                .or_insert(0) += 1;
+            *statistics
+                .parameter_ranges
+                .entry(second_parameter - first_parameter)
+                .or_insert(0) += 1;
+
+            for strategy in [
+                PasswordValidationStrategy::LetterRepetitionRange,
+                PasswordValidationStrategy::LetterPositions,
+            ]
+            .iter()
+            {
+                if let Some(reason) = strategy.diagnose_unsatisfiable_rule(entry) {
+                    statistics
+                        .unsatisfiable_rules
+                        .push((entry, *strategy, reason));
+                }
+            }
+        }
+
+        statistics
+    }
+}
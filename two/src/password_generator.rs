@@ -0,0 +1,426 @@
+use crate::password_database::PasswordValidationMetadata;
+use crate::password_policy::PasswordValidationStrategy;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Characters that generated passwords are filled out with, besides their
+/// letter.
+const PASSWORD_GENERATOR_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// Longest password, in code points, that is ever generated.
+const MAX_GENERATED_PASSWORD_LENGTH: i64 = 256;
+
+/// Most code points that a generated password is padded with beyond what its
+/// rule calls for.
+const MAX_GENERATED_PASSWORD_PADDING: i64 = 8;
+
+/// Generates passwords, and the rules they are checked against, at random.
+///
+/// Everything generated is fully determined by the random number generator,
+/// so a `PasswordGenerator` created with `from_seed(...)` always generates the
+/// same passwords for the same seed.
+pub struct PasswordGenerator<R> {
+    /// Source of every random choice made by this generator.
+    rng: R,
+}
+
+impl PasswordGenerator<StdRng> {
+    /// Creates a new `PasswordGenerator` whose output is reproducible from
+    /// `seed`.
+    pub fn from_seed(seed: u64) -> PasswordGenerator<StdRng> {
+        PasswordGenerator::new(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> PasswordGenerator<R> {
+    /// Creates a new `PasswordGenerator` that draws on `rng`.
+    pub fn new(rng: R) -> PasswordGenerator<R> {
+        PasswordGenerator { rng }
+    }
+
+    /// Generates a rule for a random letter, with parameters in order and no
+    /// greater than `max_parameter`.
+    pub fn generate_rule(&mut self, max_parameter: i64) -> PasswordValidationMetadata {
+        let letter = self.generate_filler(None);
+        let first_parameter = self.rng.gen_range(0..=max_parameter);
+        let second_parameter = self.rng.gen_range(0..=max_parameter);
+
+        PasswordValidationMetadata {
+            letter: letter.to_string(),
+            parameters: (
+                first_parameter.min(second_parameter),
+                first_parameter.max(second_parameter),
+            ),
+        }
+    }
+
+    /// Generates a random password that complies with `rule`, as interpreted
+    /// by `strategy`.
+    ///
+    /// Returns `None` if no password of reasonable length complies with
+    /// `rule`, or if its letter is not a single code point, which is all that
+    /// `PasswordValidationStrategy` ever matches.
+    pub fn generate(
+        &mut self,
+        rule: &PasswordValidationMetadata,
+        strategy: PasswordValidationStrategy,
+    ) -> Option<String> {
+        let letter = single_code_point(&rule.letter)?;
+        let (first_parameter, second_parameter) = rule.parameters;
+
+        let password = match strategy {
+            PasswordValidationStrategy::LetterRepetitionRange => {
+                let (min_repetitions, max_repetitions) =
+                    feasible_repetitions(first_parameter, second_parameter)?;
+                let repetitions = self.rng.gen_range(min_repetitions..=max_repetitions);
+
+                self.generate_with_repetitions(letter, repetitions)
+            }
+            PasswordValidationStrategy::LetterPositions => {
+                let (position, other_position) =
+                    self.choose_letter_position(first_parameter, second_parameter)?;
+
+                self.generate_with_letter_at(letter, position, other_position, position)
+            }
+        };
+
+        Some(password.into_iter().collect())
+    }
+
+    /// Generates a random password that just barely fails to comply with
+    /// `rule`, as interpreted by `strategy`: changing a single code point of
+    /// it would make it comply.
+    ///
+    /// Returns `None` if `generate(...)` would, since then there is no
+    /// compliant password to barely miss.
+    pub fn generate_counter_example(
+        &mut self,
+        rule: &PasswordValidationMetadata,
+        strategy: PasswordValidationStrategy,
+    ) -> Option<String> {
+        let letter = single_code_point(&rule.letter)?;
+        let (first_parameter, second_parameter) = rule.parameters;
+
+        let password = match strategy {
+            PasswordValidationStrategy::LetterRepetitionRange => {
+                let (min_repetitions, max_repetitions) =
+                    feasible_repetitions(first_parameter, second_parameter)?;
+
+                // One letter short of the fewest repetitions allowed, or one
+                // letter past the most.
+                let repetition_candidates = [min_repetitions - 1, max_repetitions + 1];
+                let repetitions = *repetition_candidates
+                    .iter()
+                    .copied()
+                    .filter(|repetitions| {
+                        *repetitions >= 0
+                            && *repetitions <= MAX_GENERATED_PASSWORD_LENGTH
+                            && !(first_parameter..=second_parameter).contains(repetitions)
+                    })
+                    .collect::<Vec<i64>>()
+                    .choose(&mut self.rng)?;
+
+                self.generate_with_repetitions(letter, repetitions)
+            }
+            PasswordValidationStrategy::LetterPositions => {
+                let (position, other_position) =
+                    self.choose_letter_position(first_parameter, second_parameter)?;
+
+                match other_position {
+                    // Both positions hold the letter.
+                    Some(other_position) if self.rng.gen_bool(0.5) => {
+                        let mut password = self.generate_with_letter_at(
+                            letter,
+                            position,
+                            Some(other_position),
+                            position.max(other_position),
+                        );
+                        password[(other_position - 1) as usize] = letter;
+
+                        password
+                    }
+                    // Neither position holds the letter.
+                    _ => {
+                        let mut password = self.generate_with_letter_at(
+                            letter,
+                            position,
+                            other_position,
+                            position,
+                        );
+                        password[(position - 1) as usize] = self.generate_filler(Some(letter));
+
+                        password
+                    }
+                }
+            }
+        };
+
+        Some(password.into_iter().collect())
+    }
+
+    /// Picks which of `first_position` and `second_position` is to hold the
+    /// letter, returning it along with the other position if that one is
+    /// within reach of a generated password.
+    ///
+    /// Returns `None` if neither position can hold the letter on its own.
+    fn choose_letter_position(
+        &mut self,
+        first_position: i64,
+        second_position: i64,
+    ) -> Option<(i64, Option<i64>)> {
+        if first_position == second_position {
+            return None;
+        }
+
+        let is_reachable = |position: i64| (1..=MAX_GENERATED_PASSWORD_LENGTH).contains(&position);
+        let reachable = |position: i64| Some(position).filter(|position| is_reachable(*position));
+
+        [
+            (first_position, reachable(second_position)),
+            (second_position, reachable(first_position)),
+        ]
+        .iter()
+        .filter(|(position, _)| is_reachable(*position))
+        .copied()
+        .collect::<Vec<(i64, Option<i64>)>>()
+        .choose(&mut self.rng)
+        .copied()
+    }
+
+    /// Generates a password in which `letter` appears exactly `repetitions`
+    /// times, shuffled in among some filler.
+    ///
+    /// There is always at least one filler character if `repetitions` is `0`,
+    /// since an empty password cannot be written to a password database file.
+    fn generate_with_repetitions(&mut self, letter: char, repetitions: i64) -> Vec<char> {
+        let min_padding = if repetitions == 0 { 1 } else { 0 };
+        let padding = self
+            .rng
+            .gen_range(min_padding..=MAX_GENERATED_PASSWORD_PADDING)
+            .min(MAX_GENERATED_PASSWORD_LENGTH - repetitions);
+
+        let mut password = vec![letter; repetitions as usize];
+        for _ in 0..padding {
+            password.push(self.generate_filler(Some(letter)));
+        }
+        password.shuffle(&mut self.rng);
+
+        password
+    }
+
+    /// Generates a password at least `min_length` code points long in which
+    /// one-based `position` holds `letter` and `other_position`, if there is
+    /// one, does not.
+    ///
+    /// Every other position holds `letter` every so often.
+    fn generate_with_letter_at(
+        &mut self,
+        letter: char,
+        position: i64,
+        other_position: Option<i64>,
+        min_length: i64,
+    ) -> Vec<char> {
+        let max_length =
+            (min_length + MAX_GENERATED_PASSWORD_PADDING).min(MAX_GENERATED_PASSWORD_LENGTH);
+        let length = self.rng.gen_range(min_length..=max_length);
+
+        let mut password = (0..length)
+            .map(|_| {
+                if self.rng.gen_ratio(1, 4) {
+                    letter
+                } else {
+                    self.generate_filler(Some(letter))
+                }
+            })
+            .collect::<Vec<char>>();
+
+        password[(position - 1) as usize] = letter;
+        if let Some(other_position) =
+            other_position.filter(|other_position| *other_position <= length)
+        {
+            password[(other_position - 1) as usize] = self.generate_filler(Some(letter));
+        }
+
+        password
+    }
+
+    /// Picks a random character of `PASSWORD_GENERATOR_ALPHABET` other than
+    /// `letter`.
+    fn generate_filler(&mut self, letter: Option<char>) -> char {
+        *PASSWORD_GENERATOR_ALPHABET
+            .chars()
+            .filter(|character| Some(*character) != letter)
+            .collect::<Vec<char>>()
+            .choose(&mut self.rng)
+            .unwrap()
+    }
+}
+
+/// Returns the only code point of `letter`, if it is made up of exactly one.
+fn single_code_point(letter: &str) -> Option<char> {
+    let mut code_points = letter.chars();
+
+    match (code_points.next(), code_points.next()) {
+        (Some(code_point), None) => Some(code_point),
+        _ => None,
+    }
+}
+
+/// Narrows the repetition range from `min_repetitions` to `max_repetitions` to
+/// what a generated password can hold, returning `None` if nothing is left.
+fn feasible_repetitions(min_repetitions: i64, max_repetitions: i64) -> Option<(i64, i64)> {
+    let min_repetitions = min_repetitions.max(0);
+    let max_repetitions = max_repetitions.min(MAX_GENERATED_PASSWORD_LENGTH);
+
+    if min_repetitions <= max_repetitions {
+        Some((min_repetitions, max_repetitions))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::password_database::{PasswordDatabase, PasswordDatabaseEntry};
+    use crate::password_policy::PasswordPolicy;
+
+    /// Every `PasswordValidationStrategy`.
+    const STRATEGIES: [PasswordValidationStrategy; 2] = [
+        PasswordValidationStrategy::LetterRepetitionRange,
+        PasswordValidationStrategy::LetterPositions,
+    ];
+
+    /// Creates a rule for `letter` with the given `parameters`.
+    fn rule(letter: &str, parameters: (i64, i64)) -> PasswordValidationMetadata {
+        PasswordValidationMetadata {
+            letter: letter.to_owned(),
+            parameters,
+        }
+    }
+
+    /// Returns `true` if `password` complies with `rule`, as interpreted by
+    /// `strategy`.
+    fn is_valid(
+        password: &str,
+        rule: &PasswordValidationMetadata,
+        strategy: PasswordValidationStrategy,
+    ) -> bool {
+        strategy.is_valid(&PasswordDatabaseEntry {
+            line_number: 1,
+            password: password.to_owned(),
+            validation_metadata: rule.clone(),
+        })
+    }
+
+    #[test]
+    fn generates_passwords_on_either_side_of_rule() {
+        let mut password_generator = PasswordGenerator::from_seed(2020);
+        let mut number_of_checked_passwords = 0;
+
+        for _ in 0..500 {
+            let rule = password_generator.generate_rule(20);
+
+            for strategy in STRATEGIES {
+                if let Some(password) = password_generator.generate(&rule, strategy) {
+                    assert!(is_valid(&password, &rule, strategy), "{}", password);
+                    number_of_checked_passwords += 1;
+                }
+                if let Some(password) = password_generator.generate_counter_example(&rule, strategy)
+                {
+                    assert!(!is_valid(&password, &rule, strategy), "{}", password);
+                    number_of_checked_passwords += 1;
+                }
+            }
+        }
+
+        assert!(number_of_checked_passwords > 1000);
+    }
+
+    #[test]
+    fn generates_same_passwords_for_same_seed() {
+        let generate_all = |seed: u64| {
+            let mut password_generator = PasswordGenerator::from_seed(seed);
+
+            (0..50)
+                .flat_map(|_| {
+                    let rule = password_generator.generate_rule(20);
+
+                    STRATEGIES
+                        .iter()
+                        .flat_map(|strategy| {
+                            [
+                                password_generator.generate(&rule, *strategy),
+                                password_generator.generate_counter_example(&rule, *strategy),
+                            ]
+                        })
+                        .collect::<Vec<Option<String>>>()
+                })
+                .collect::<Vec<Option<String>>>()
+        };
+
+        assert_eq!(generate_all(7), generate_all(7));
+        assert_ne!(generate_all(7), generate_all(8));
+    }
+
+    #[test]
+    fn generates_nothing_for_unsatisfiable_rules() {
+        let mut password_generator = PasswordGenerator::from_seed(1);
+        let unsatisfiable_rules = [
+            (
+                PasswordValidationStrategy::LetterPositions,
+                rule("a", (3, 3)),
+            ),
+            (
+                PasswordValidationStrategy::LetterRepetitionRange,
+                rule("a", (3, 1)),
+            ),
+            (
+                PasswordValidationStrategy::LetterRepetitionRange,
+                rule("e\u{301}", (1, 3)),
+            ),
+            (
+                PasswordValidationStrategy::LetterPositions,
+                rule("e\u{301}", (1, 3)),
+            ),
+        ];
+
+        for (strategy, rule) in unsatisfiable_rules.iter() {
+            assert_eq!(password_generator.generate(rule, *strategy), None);
+            assert_eq!(
+                password_generator.generate_counter_example(rule, *strategy),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn generates_readable_passwords_without_repetitions() {
+        let mut password_generator = PasswordGenerator::from_seed(1);
+        let strategy = PasswordValidationStrategy::LetterRepetitionRange;
+
+        for (rule_text, parameters, is_counter_example) in
+            [("0-0", (0, 0), false), ("1-3", (1, 3), true)]
+        {
+            let rule = rule("a", parameters);
+
+            for _ in 0..100 {
+                let password = if is_counter_example {
+                    password_generator.generate_counter_example(&rule, strategy)
+                } else {
+                    password_generator.generate(&rule, strategy)
+                }
+                .unwrap();
+                let entry_text = format!("{} a: {}", rule_text, password);
+
+                assert!(!password.is_empty(), "{}", entry_text);
+                assert!(
+                    PasswordDatabase::from_file(&entry_text).is_ok(),
+                    "{}",
+                    entry_text
+                );
+            }
+        }
+    }
+}
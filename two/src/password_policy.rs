@@ -0,0 +1,521 @@
+use crate::password_database::{PasswordDatabaseEntry, PasswordValidationMetadata};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Decides whether the password of a password database entry is valid.
+///
+/// Implemented for any `Fn(&PasswordDatabaseEntry) -> bool`, so one-off
+/// policies can be written as closures.
+pub trait PasswordPolicy {
+    /// Returns `Err(...)` explaining why if the password of `entry` does not
+    /// comply with this policy.
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation>;
+
+    /// Returns `true` if the password of `entry` complies with this policy.
+    fn is_valid(&self, entry: &PasswordDatabaseEntry) -> bool {
+        self.validate(entry).is_ok()
+    }
+}
+
+impl<F> PasswordPolicy for F
+where
+    F: Fn(&PasswordDatabaseEntry) -> bool,
+{
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        if self(entry) {
+            Ok(())
+        } else {
+            Err(PasswordPolicyViolation::new(
+                "password was rejected by a custom policy".to_owned(),
+            ))
+        }
+    }
+}
+
+/// Explains why a password does not comply with a `PasswordPolicy`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordPolicyViolation {
+    /// Human-readable description of what is wrong with the password.
+    pub reason: String,
+}
+
+impl PasswordPolicyViolation {
+    /// Creates a new `PasswordPolicyViolation`.
+    pub fn new(reason: String) -> PasswordPolicyViolation {
+        PasswordPolicyViolation { reason }
+    }
+}
+
+impl Display for PasswordPolicyViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl Error for PasswordPolicyViolation {}
+
+/// Enumerates every way of splitting a password into the units that letters,
+/// positions and repetitions refer to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PasswordSegmentation {
+    /// Splits passwords into Unicode code points (`char`s).
+    ///
+    /// Used by default.
+    ///
+    /// A letter made up of several code points, like `é` spelled as `e`
+    /// followed by a combining acute accent, or most emoji, never matches
+    /// any single code point.
+    #[default]
+    CodePoints,
+    /// Splits passwords into extended grapheme clusters, as described by
+    /// [UAX #29](https://www.unicode.org/reports/tr29/).
+    ///
+    /// Each cluster is what a reader would think of as one character: a
+    /// letter along with its combining marks, or a whole emoji sequence. A
+    /// letter only matches a cluster that is exactly equal to it, so `e` does
+    /// not match `é`.
+    GraphemeClusters,
+}
+
+impl PasswordSegmentation {
+    /// Splits `password` into units according to this segmentation.
+    pub fn segment<'a>(&self, password: &'a str) -> Vec<&'a str> {
+        match self {
+            PasswordSegmentation::CodePoints => password
+                .char_indices()
+                .map(|(index, password_char)| &password[index..(index + password_char.len_utf8())])
+                .collect(),
+            PasswordSegmentation::GraphemeClusters => password.graphemes(true).collect(),
+        }
+    }
+
+    /// Counts how many units of `password` are equal to `letter`.
+    pub fn count(&self, letter: &str, password: &str) -> usize {
+        self.segment(password)
+            .into_iter()
+            .filter(|unit| *unit == letter)
+            .count()
+    }
+
+    /// Returns the unit of `password` at the specified (one-based)
+    /// `position`, if there is one.
+    pub fn unit_at<'a>(&self, password: &'a str, position: i64) -> Option<&'a str> {
+        if position < 1 {
+            return None;
+        }
+
+        self.segment(password).get((position - 1) as usize).copied()
+    }
+}
+
+impl Display for PasswordSegmentation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordSegmentation::CodePoints => write!(f, "code points"),
+            PasswordSegmentation::GraphemeClusters => write!(f, "grapheme clusters"),
+        }
+    }
+}
+
+/// Interprets `PasswordValidationMetadata#parameters` as a repetition range
+/// where the first value is the (inclusive) minimum number of letter
+/// repetitions in a valid password and the second value is the (inclusive)
+/// maximum number of repetitions in a valid password.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LetterRepetitionRange {
+    /// How passwords are split up before repetitions are counted.
+    pub segmentation: PasswordSegmentation,
+}
+
+impl PasswordPolicy for LetterRepetitionRange {
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        let PasswordDatabaseEntry {
+            password,
+            validation_metadata:
+                PasswordValidationMetadata {
+                    letter,
+                    parameters: (min_repetitions, max_repetitions),
+                },
+            ..
+        } = entry;
+
+        let repetitions = self.segmentation.count(letter, password) as i64;
+
+        if repetitions >= *min_repetitions && repetitions <= *max_repetitions {
+            Ok(())
+        } else {
+            Err(PasswordPolicyViolation::new(format!(
+                "letter '{}' appears {} {}, allowed {}–{}",
+                letter,
+                repetitions,
+                if repetitions == 1 { "time" } else { "times" },
+                min_repetitions,
+                max_repetitions
+            )))
+        }
+    }
+}
+
+/// Interprets `PasswordValidationMetadata#parameters` as legitimate positions
+/// for the letter in a valid password, exactly one of which must hold the
+/// letter.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LetterPositions {
+    /// How passwords are split up before positions are counted.
+    pub segmentation: PasswordSegmentation,
+}
+
+impl PasswordPolicy for LetterPositions {
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        let PasswordDatabaseEntry {
+            password,
+            validation_metadata:
+                PasswordValidationMetadata {
+                    letter,
+                    parameters: (first_position, second_position),
+                },
+            ..
+        } = entry;
+
+        let does_position_match =
+            |position: i64| self.segmentation.unit_at(password, position) == Some(letter.as_str());
+        let does_first_position_match = does_position_match(*first_position);
+        let does_second_position_match = does_position_match(*second_position);
+
+        match (does_first_position_match, does_second_position_match) {
+            (true, true) => Err(PasswordPolicyViolation::new(format!(
+                "positions {} and {} both contain '{}'",
+                first_position, second_position, letter
+            ))),
+            (false, false) => Err(PasswordPolicyViolation::new(format!(
+                "neither position {} nor {} contains '{}'",
+                first_position, second_position, letter
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Enumerates every way that `PasswordValidationMetadata` can be used.
+///
+/// Passwords are split into code points; use `LetterRepetitionRange` or
+/// `LetterPositions` directly for other kinds of `PasswordSegmentation`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PasswordValidationStrategy {
+    /// Validates passwords according to `LetterRepetitionRange`.
+    LetterRepetitionRange,
+    /// Validates passwords according to `LetterPositions`.
+    LetterPositions,
+}
+
+impl PasswordValidationStrategy {
+    /// Returns the `PasswordValidationStrategy` named by `name`, either
+    /// `repetition-range` or `letter-positions`.
+    pub fn from_name(name: &str) -> Result<PasswordValidationStrategy, anyhow::Error> {
+        match name {
+            "repetition-range" => Ok(PasswordValidationStrategy::LetterRepetitionRange),
+            "letter-positions" => Ok(PasswordValidationStrategy::LetterPositions),
+            _ => Err(anyhow::Error::msg(format!(
+                "Unrecognized password validation strategy \"{}\"",
+                name
+            ))),
+        }
+    }
+
+    /// Returns `Some(...)` explaining why if no password of the same length
+    /// as that of `entry` could ever comply with its rule, as interpreted by
+    /// this strategy.
+    pub fn diagnose_unsatisfiable_rule(&self, entry: &PasswordDatabaseEntry) -> Option<String> {
+        let (first_parameter, second_parameter) = entry.validation_metadata.parameters;
+        let password_length = PasswordSegmentation::CodePoints
+            .segment(&entry.password)
+            .len() as i64;
+
+        match self {
+            PasswordValidationStrategy::LetterRepetitionRange => {
+                if first_parameter > second_parameter {
+                    Some(format!(
+                        "minimum of {} repetitions exceeds maximum of {}",
+                        first_parameter, second_parameter
+                    ))
+                } else if second_parameter < 0 {
+                    Some(format!(
+                        "maximum of {} repetitions is negative",
+                        second_parameter
+                    ))
+                } else if first_parameter > password_length {
+                    Some(format!(
+                        "minimum of {} repetitions exceeds password length of {}",
+                        first_parameter, password_length
+                    ))
+                } else {
+                    None
+                }
+            }
+            PasswordValidationStrategy::LetterPositions => {
+                let is_within_password = |position: i64| (1..=password_length).contains(&position);
+
+                if first_parameter == second_parameter {
+                    Some(format!(
+                        "positions {} and {} are the same, so they always agree",
+                        first_parameter, second_parameter
+                    ))
+                } else if !is_within_password(first_parameter)
+                    && !is_within_password(second_parameter)
+                {
+                    Some(format!(
+                        "positions {} and {} are both beyond password length of {}",
+                        first_parameter, second_parameter, password_length
+                    ))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl Display for PasswordValidationStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordValidationStrategy::LetterRepetitionRange => write!(f, "repetition-range"),
+            PasswordValidationStrategy::LetterPositions => write!(f, "letter-positions"),
+        }
+    }
+}
+
+impl PasswordPolicy for PasswordValidationStrategy {
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        match self {
+            PasswordValidationStrategy::LetterRepetitionRange => {
+                LetterRepetitionRange::default().validate(entry)
+            }
+            PasswordValidationStrategy::LetterPositions => {
+                LetterPositions::default().validate(entry)
+            }
+        }
+    }
+}
+
+/// Requires passwords to be at least `min_length` characters long, where each
+/// grapheme cluster counts as one character.
+pub struct MinimumLength {
+    /// Fewest characters allowed in a valid password.
+    pub min_length: usize,
+}
+
+impl PasswordPolicy for MinimumLength {
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        let length = entry.password.graphemes(true).count();
+
+        if length >= self.min_length {
+            Ok(())
+        } else {
+            Err(PasswordPolicyViolation::new(format!(
+                "password has {} characters, at least {} required",
+                length, self.min_length
+            )))
+        }
+    }
+}
+
+/// Enumerates every kind of character that `RequiredCharacterClasses` can
+/// demand.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CharacterClass {
+    /// Lowercase letters, like `a`.
+    Lowercase,
+    /// Uppercase letters, like `A`.
+    Uppercase,
+    /// Numeric digits, like `1`.
+    Digit,
+    /// Anything that is neither a letter nor a digit, like `!`.
+    Symbol,
+}
+
+impl CharacterClass {
+    /// Returns `true` if `character` belongs to this class.
+    pub fn contains(&self, character: char) -> bool {
+        match self {
+            CharacterClass::Lowercase => character.is_lowercase(),
+            CharacterClass::Uppercase => character.is_uppercase(),
+            CharacterClass::Digit => character.is_numeric(),
+            CharacterClass::Symbol => !character.is_alphanumeric(),
+        }
+    }
+}
+
+impl Display for CharacterClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CharacterClass::Lowercase => write!(f, "lowercase letters"),
+            CharacterClass::Uppercase => write!(f, "uppercase letters"),
+            CharacterClass::Digit => write!(f, "digits"),
+            CharacterClass::Symbol => write!(f, "symbols"),
+        }
+    }
+}
+
+/// Requires passwords to contain at least one character of each of the
+/// `character_classes`.
+pub struct RequiredCharacterClasses {
+    /// Every class of character that must appear in a valid password.
+    pub character_classes: Vec<CharacterClass>,
+}
+
+impl PasswordPolicy for RequiredCharacterClasses {
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        let missing_character_classes = self
+            .character_classes
+            .iter()
+            .filter(|character_class| {
+                !entry
+                    .password
+                    .chars()
+                    .any(|password_char| character_class.contains(password_char))
+            })
+            .map(|character_class| character_class.to_string())
+            .collect::<Vec<String>>();
+
+        if missing_character_classes.is_empty() {
+            Ok(())
+        } else {
+            Err(PasswordPolicyViolation::new(format!(
+                "password contains no {}",
+                missing_character_classes.join(", no ")
+            )))
+        }
+    }
+}
+
+/// Requires passwords to contain none of the `substrings`.
+pub struct ForbiddenSubstrings {
+    /// Every string that must not appear in a valid password.
+    pub substrings: Vec<String>,
+}
+
+impl PasswordPolicy for ForbiddenSubstrings {
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        match self
+            .substrings
+            .iter()
+            .find(|substring| entry.password.contains(substring.as_str()))
+        {
+            Some(substring) => Err(PasswordPolicyViolation::new(format!(
+                "password contains forbidden substring \"{}\"",
+                substring
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A named collection of password policies that can be run against every
+/// entry of a password database at once.
+#[derive(Default)]
+pub struct PasswordPolicyRegistry {
+    /// Every registered policy along with its name, in order of registration.
+    policies: Vec<(String, Box<dyn PasswordPolicy>)>,
+}
+
+impl PasswordPolicyRegistry {
+    /// Creates a new, empty `PasswordPolicyRegistry`.
+    pub fn new() -> PasswordPolicyRegistry {
+        PasswordPolicyRegistry::default()
+    }
+
+    /// Adds `policy` to this registry under the given `name`.
+    pub fn register<P>(&mut self, name: &str, policy: P) -> &mut PasswordPolicyRegistry
+    where
+        P: PasswordPolicy + 'static,
+    {
+        self.policies.push((name.to_owned(), Box::new(policy)));
+
+        self
+    }
+
+    /// Iterates through every registered policy along with its name, in
+    /// order of registration.
+    pub fn policies(&self) -> impl Iterator<Item = (&str, &dyn PasswordPolicy)> {
+        self.policies
+            .iter()
+            .map(|(name, policy)| (name.as_str(), policy.as_ref()))
+    }
+
+    /// Returns the policy registered under the given `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&dyn PasswordPolicy> {
+        self.policies()
+            .find(|(policy_name, _)| *policy_name == name)
+            .map(|(_, policy)| policy)
+    }
+
+    /// Counts how many of the `entries` are valid under each registered
+    /// policy, in order of registration.
+    pub fn count_valid_entries(&self, entries: &[PasswordDatabaseEntry]) -> Vec<(&str, usize)> {
+        self.policies
+            .iter()
+            .map(|(name, policy)| {
+                (
+                    name.as_str(),
+                    entries
+                        .iter()
+                        .filter(|entry| entry.is_valid(policy.as_ref()))
+                        .count(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `é` spelled as `e` followed by a combining acute accent.
+    const COMBINED_E_ACUTE: &str = "e\u{301}";
+
+    /// Family emoji made up of three people joined by zero-width joiners.
+    const FAMILY_EMOJI: &str = "👩\u{200d}👩\u{200d}👧";
+
+    /// Thumbs up emoji with a skin tone modifier.
+    const THUMBS_UP_EMOJI: &str = "👍\u{1f3fd}";
+
+    #[test]
+    fn segments_code_points() {
+        let password = format!("a{}{}", COMBINED_E_ACUTE, FAMILY_EMOJI);
+        let segmentation = PasswordSegmentation::CodePoints;
+
+        assert_eq!(
+            segmentation.segment(&password),
+            ["a", "e", "\u{301}", "👩", "\u{200d}", "👩", "\u{200d}", "👧"]
+        );
+        assert_eq!(segmentation.count("👩", &password), 2);
+        assert_eq!(segmentation.count(COMBINED_E_ACUTE, &password), 0);
+        assert_eq!(segmentation.count(FAMILY_EMOJI, &password), 0);
+        assert_eq!(segmentation.unit_at(&password, 2), Some("e"));
+        assert_eq!(segmentation.unit_at(&password, 3), Some("\u{301}"));
+        assert_eq!(segmentation.unit_at(&password, 0), None);
+        assert_eq!(segmentation.unit_at(&password, 9), None);
+    }
+
+    #[test]
+    fn segments_grapheme_clusters() {
+        let password = format!("a{}{}{}", COMBINED_E_ACUTE, FAMILY_EMOJI, THUMBS_UP_EMOJI);
+        let segmentation = PasswordSegmentation::GraphemeClusters;
+
+        assert_eq!(
+            segmentation.segment(&password),
+            ["a", COMBINED_E_ACUTE, FAMILY_EMOJI, THUMBS_UP_EMOJI]
+        );
+        assert_eq!(segmentation.count("e", &password), 0);
+        assert_eq!(segmentation.count(COMBINED_E_ACUTE, &password), 1);
+        assert_eq!(segmentation.count("👩", &password), 0);
+        assert_eq!(segmentation.count(FAMILY_EMOJI, &password), 1);
+        assert_eq!(segmentation.count("👍", &password), 0);
+        assert_eq!(segmentation.unit_at(&password, 2), Some(COMBINED_E_ACUTE));
+        assert_eq!(segmentation.unit_at(&password, 3), Some(FAMILY_EMOJI));
+        assert_eq!(segmentation.unit_at(&password, 4), Some(THUMBS_UP_EMOJI));
+        assert_eq!(segmentation.unit_at(&password, 5), None);
+    }
+}
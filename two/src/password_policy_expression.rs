@@ -0,0 +1,920 @@
+use crate::password_database::{PasswordDatabaseEntry, PasswordValidationMetadata};
+use crate::password_policy::{PasswordPolicy, PasswordPolicyViolation, PasswordSegmentation};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::CharIndices;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A password policy written as text, like
+/// `count(letter) in first..=second`.
+///
+/// Expressions are made up of:
+///
+/// | syntax                          | meaning                                                |
+/// |---------------------------------|--------------------------------------------------------|
+/// | `1`, `42`                       | integer                                                |
+/// | `a`, `'a'`                      | character (quotes are needed for anything but a-z)     |
+/// | `true`, `false`                 | boolean                                                |
+/// | `letter`                        | `PasswordValidationMetadata#letter`                    |
+/// | `first`, `second`               | `PasswordValidationMetadata#parameters`                |
+/// | `len()`                         | number of characters in the password                   |
+/// | `count(c)`                      | number of times character `c` appears in the password  |
+/// | `pos(n)`                        | character at (one-based) position `n` of the password  |
+/// | `glen()`, `gcount(c)`, `gpos(n)` | like the above, but for grapheme clusters             |
+/// | `==`, `!=`                      | equality of two integers, characters or booleans       |
+/// | `<`, `<=`, `>`, `>=`            | ordering of two integers                               |
+/// | `x in a..=b`, `x in a..b`       | inclusive or exclusive integer range                   |
+/// | `!`, `&&`, `^`, `\|\|`           | boolean operators, from tightest to loosest binding    |
+/// | `(...)`                         | grouping                                               |
+///
+/// Characters are single grapheme clusters. `len()`, `count(c)` and `pos(n)`
+/// split passwords into code points, while `glen()`, `gcount(c)` and
+/// `gpos(n)` split them into grapheme clusters; see `PasswordSegmentation` for
+/// how the two differ. Positions beyond either end of the password hold no
+/// character, which is not equal to any actual character.
+#[derive(Debug)]
+pub struct PasswordPolicyExpression {
+    /// Root of the parsed syntax tree.
+    root: Node,
+    /// Text from which this expression was parsed.
+    source: String,
+}
+
+impl PasswordPolicyExpression {
+    /// Parses `source` into a new `PasswordPolicyExpression`.
+    ///
+    /// Returns `Err(...)` pointing at the offending part of `source` if it is
+    /// malformed, or if it does not evaluate to a boolean.
+    pub fn parse(source: &str) -> Result<PasswordPolicyExpression, PasswordPolicyExpressionError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+
+        let root = parser.parse_expression()?;
+        parser.expect(&TokenKind::End)?;
+
+        let root_type = root.check_type()?;
+        if root_type != ValueType::Boolean {
+            return Err(PasswordPolicyExpressionError::new(
+                format!(
+                    "Expected the expression to be a boolean, but it is {}",
+                    root_type
+                ),
+                root.span,
+            ));
+        }
+
+        Ok(PasswordPolicyExpression {
+            root,
+            source: source.to_owned(),
+        })
+    }
+}
+
+impl PasswordPolicy for PasswordPolicyExpression {
+    fn validate(&self, entry: &PasswordDatabaseEntry) -> Result<(), PasswordPolicyViolation> {
+        if self.root.evaluate_boolean(entry) {
+            Ok(())
+        } else {
+            Err(PasswordPolicyViolation::new(format!(
+                "`{}` does not hold",
+                self.source
+            )))
+        }
+    }
+}
+
+/// Explains why some text could not be parsed into a
+/// `PasswordPolicyExpression`.
+#[derive(Debug)]
+pub struct PasswordPolicyExpressionError {
+    /// Describes what went wrong.
+    pub message: String,
+    /// Byte range of the text responsible for this error.
+    pub span: Span,
+}
+
+impl PasswordPolicyExpressionError {
+    /// Creates a new `PasswordPolicyExpressionError`.
+    fn new(message: String, span: Span) -> PasswordPolicyExpressionError {
+        PasswordPolicyExpressionError { message, span }
+    }
+
+    /// Renders `source`, the text that failed to parse, with this error's
+    /// span underlined beneath it.
+    pub fn underline(&self, source: &str) -> String {
+        let padding = source[..self.span.start].chars().count();
+        let width = source[self.span.start..self.span.end]
+            .chars()
+            .count()
+            .max(1);
+
+        format!("{}\n{}{}", source, " ".repeat(padding), "^".repeat(width))
+    }
+}
+
+impl Display for PasswordPolicyExpressionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (at {}..{})",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl Error for PasswordPolicyExpressionError {}
+
+/// Byte range of the text of a `PasswordPolicyExpression`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Span {
+    /// Index of the first byte in this range.
+    pub start: usize,
+    /// Index of the first byte after this range.
+    pub end: usize,
+}
+
+impl Span {
+    /// Returns the smallest span covering both this span and `other`.
+    fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+/// Enumerates every kind of token in the text of a `PasswordPolicyExpression`.
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Integer(i64),
+    Character(String),
+    Identifier(String),
+    LeftParenthesis,
+    RightParenthesis,
+    Bang,
+    DoubleAmpersand,
+    Caret,
+    DoublePipe,
+    DoubleEquals,
+    BangEquals,
+    Less,
+    LessEquals,
+    Greater,
+    GreaterEquals,
+    DoubleDot,
+    DoubleDotEquals,
+    End,
+}
+
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Integer(integer) => write!(f, "`{}`", integer),
+            TokenKind::Character(character) => write!(f, "`'{}'`", character),
+            TokenKind::Identifier(identifier) => write!(f, "`{}`", identifier),
+            TokenKind::LeftParenthesis => write!(f, "`(`"),
+            TokenKind::RightParenthesis => write!(f, "`)`"),
+            TokenKind::Bang => write!(f, "`!`"),
+            TokenKind::DoubleAmpersand => write!(f, "`&&`"),
+            TokenKind::Caret => write!(f, "`^`"),
+            TokenKind::DoublePipe => write!(f, "`||`"),
+            TokenKind::DoubleEquals => write!(f, "`==`"),
+            TokenKind::BangEquals => write!(f, "`!=`"),
+            TokenKind::Less => write!(f, "`<`"),
+            TokenKind::LessEquals => write!(f, "`<=`"),
+            TokenKind::Greater => write!(f, "`>`"),
+            TokenKind::GreaterEquals => write!(f, "`>=`"),
+            TokenKind::DoubleDot => write!(f, "`..`"),
+            TokenKind::DoubleDotEquals => write!(f, "`..=`"),
+            TokenKind::End => write!(f, "end of expression"),
+        }
+    }
+}
+
+/// A single token in the text of a `PasswordPolicyExpression`.
+#[derive(Clone, Debug)]
+struct Token {
+    /// What kind of token this is.
+    kind: TokenKind,
+    /// Where this token appears.
+    span: Span,
+}
+
+/// Splits `source` into tokens, ending with `TokenKind::End`.
+fn tokenize(source: &str) -> Result<Vec<Token>, PasswordPolicyExpressionError> {
+    let mut tokens = vec![];
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((start, source_char)) = chars.next() {
+        if source_char.is_whitespace() {
+            continue;
+        }
+
+        let mut end = start + source_char.len_utf8();
+
+        let kind = match source_char {
+            '(' => TokenKind::LeftParenthesis,
+            ')' => TokenKind::RightParenthesis,
+            '^' => TokenKind::Caret,
+            '!' if consume_if(&mut chars, '=', &mut end) => TokenKind::BangEquals,
+            '!' => TokenKind::Bang,
+            '&' if consume_if(&mut chars, '&', &mut end) => TokenKind::DoubleAmpersand,
+            '|' if consume_if(&mut chars, '|', &mut end) => TokenKind::DoublePipe,
+            '=' if consume_if(&mut chars, '=', &mut end) => TokenKind::DoubleEquals,
+            '<' if consume_if(&mut chars, '=', &mut end) => TokenKind::LessEquals,
+            '<' => TokenKind::Less,
+            '>' if consume_if(&mut chars, '=', &mut end) => TokenKind::GreaterEquals,
+            '>' => TokenKind::Greater,
+            '.' if consume_if(&mut chars, '.', &mut end) => {
+                if consume_if(&mut chars, '=', &mut end) {
+                    TokenKind::DoubleDotEquals
+                } else {
+                    TokenKind::DoubleDot
+                }
+            }
+            '\'' => {
+                let closing_quote_index = chars
+                    .by_ref()
+                    .find(|(_, closing_char)| *closing_char == '\'')
+                    .map(|(closing_quote_index, _)| closing_quote_index);
+                let quoted_text = closing_quote_index
+                    .map(|closing_quote_index| &source[(start + 1)..closing_quote_index])
+                    .filter(|quoted_text| quoted_text.graphemes(true).count() == 1);
+
+                match (quoted_text, closing_quote_index) {
+                    (Some(quoted_text), Some(closing_quote_index)) => {
+                        end = closing_quote_index + 1;
+
+                        TokenKind::Character(quoted_text.to_owned())
+                    }
+                    _ => {
+                        return Err(PasswordPolicyExpressionError::new(
+                            "Expected a single character between quotes".to_owned(),
+                            Span {
+                                start,
+                                end: closing_quote_index
+                                    .map(|closing_quote_index| closing_quote_index + 1)
+                                    .unwrap_or_else(|| source.len()),
+                            },
+                        ))
+                    }
+                }
+            }
+            _ if source_char.is_ascii_digit() => {
+                while let Some((index, next_char)) = chars.peek() {
+                    if !next_char.is_ascii_digit() {
+                        break;
+                    }
+
+                    end = index + next_char.len_utf8();
+                    chars.next();
+                }
+
+                let integer = source[start..end].parse::<i64>().map_err(|_| {
+                    PasswordPolicyExpressionError::new(
+                        format!("Integer `{}` is too large", &source[start..end]),
+                        Span { start, end },
+                    )
+                })?;
+
+                TokenKind::Integer(integer)
+            }
+            _ if source_char.is_alphabetic() || source_char == '_' => {
+                while let Some((index, next_char)) = chars.peek() {
+                    if !next_char.is_alphanumeric() && *next_char != '_' {
+                        break;
+                    }
+
+                    end = index + next_char.len_utf8();
+                    chars.next();
+                }
+
+                TokenKind::Identifier(source[start..end].to_owned())
+            }
+            _ => {
+                return Err(PasswordPolicyExpressionError::new(
+                    format!("Unexpected character `{}`", &source[start..end]),
+                    Span { start, end },
+                ))
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            span: Span { start, end },
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::End,
+        span: Span {
+            start: source.len(),
+            end: source.len(),
+        },
+    });
+
+    Ok(tokens)
+}
+
+/// Consumes the next of the `chars` if it is `expected_char`, extending `end`
+/// past it.
+fn consume_if(chars: &mut Peekable<CharIndices>, expected_char: char, end: &mut usize) -> bool {
+    match chars.peek() {
+        Some((_, next_char)) if *next_char == expected_char => {
+            *end += expected_char.len_utf8();
+            chars.next();
+
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Enumerates every operator that combines two values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BinaryOperator {
+    And,
+    Xor,
+    Or,
+    Equals,
+    NotEquals,
+    Less,
+    LessEquals,
+    Greater,
+    GreaterEquals,
+}
+
+impl BinaryOperator {
+    /// Returns the operator corresponding to the given `token_kind`, if any.
+    fn from_token_kind(token_kind: &TokenKind) -> Option<BinaryOperator> {
+        match token_kind {
+            TokenKind::DoubleAmpersand => Some(BinaryOperator::And),
+            TokenKind::Caret => Some(BinaryOperator::Xor),
+            TokenKind::DoublePipe => Some(BinaryOperator::Or),
+            TokenKind::DoubleEquals => Some(BinaryOperator::Equals),
+            TokenKind::BangEquals => Some(BinaryOperator::NotEquals),
+            TokenKind::Less => Some(BinaryOperator::Less),
+            TokenKind::LessEquals => Some(BinaryOperator::LessEquals),
+            TokenKind::Greater => Some(BinaryOperator::Greater),
+            TokenKind::GreaterEquals => Some(BinaryOperator::GreaterEquals),
+            _ => None,
+        }
+    }
+}
+
+/// Enumerates every kind of node in the syntax tree of a
+/// `PasswordPolicyExpression`.
+#[derive(Debug)]
+enum NodeKind {
+    Integer(i64),
+    Character(String),
+    Boolean(bool),
+    Letter,
+    FirstParameter,
+    SecondParameter,
+    Length(PasswordSegmentation),
+    Count(PasswordSegmentation, Box<Node>),
+    Position(PasswordSegmentation, Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOperator, Box<Node>, Box<Node>),
+    InRange {
+        value: Box<Node>,
+        lower_bound: Box<Node>,
+        upper_bound: Box<Node>,
+        is_inclusive: bool,
+    },
+}
+
+/// A node in the syntax tree of a `PasswordPolicyExpression`.
+#[derive(Debug)]
+struct Node {
+    /// What kind of node this is.
+    kind: NodeKind,
+    /// Text from which this node was parsed.
+    span: Span,
+}
+
+/// Enumerates every type of value that an expression can evaluate to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ValueType {
+    Integer,
+    Character,
+    Boolean,
+}
+
+impl Display for ValueType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueType::Integer => write!(f, "an integer"),
+            ValueType::Character => write!(f, "a character"),
+            ValueType::Boolean => write!(f, "a boolean"),
+        }
+    }
+}
+
+/// A value that an expression evaluates to.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Integer(i64),
+    /// `None` stands in for a position beyond either end of the password.
+    Character(Option<String>),
+    Boolean(bool),
+}
+
+impl Node {
+    /// Works out what type of value this node evaluates to, making sure
+    /// every operand along the way has the right type.
+    fn check_type(&self) -> Result<ValueType, PasswordPolicyExpressionError> {
+        match &self.kind {
+            NodeKind::Integer(_) | NodeKind::FirstParameter | NodeKind::SecondParameter => {
+                Ok(ValueType::Integer)
+            }
+            NodeKind::Length(_) => Ok(ValueType::Integer),
+            NodeKind::Character(_) | NodeKind::Letter => Ok(ValueType::Character),
+            NodeKind::Boolean(_) => Ok(ValueType::Boolean),
+            NodeKind::Count(_, operand) => {
+                operand.expect_type(ValueType::Character)?;
+
+                Ok(ValueType::Integer)
+            }
+            NodeKind::Position(_, operand) => {
+                operand.expect_type(ValueType::Integer)?;
+
+                Ok(ValueType::Character)
+            }
+            NodeKind::Not(operand) => {
+                operand.expect_type(ValueType::Boolean)?;
+
+                Ok(ValueType::Boolean)
+            }
+            NodeKind::Binary(operator, left_operand, right_operand) => {
+                match operator {
+                    BinaryOperator::And | BinaryOperator::Xor | BinaryOperator::Or => {
+                        left_operand.expect_type(ValueType::Boolean)?;
+                        right_operand.expect_type(ValueType::Boolean)?;
+                    }
+                    BinaryOperator::Equals | BinaryOperator::NotEquals => {
+                        let left_operand_type = left_operand.check_type()?;
+
+                        right_operand.expect_type(left_operand_type)?;
+                    }
+                    BinaryOperator::Less
+                    | BinaryOperator::LessEquals
+                    | BinaryOperator::Greater
+                    | BinaryOperator::GreaterEquals => {
+                        left_operand.expect_type(ValueType::Integer)?;
+                        right_operand.expect_type(ValueType::Integer)?;
+                    }
+                }
+
+                Ok(ValueType::Boolean)
+            }
+            NodeKind::InRange {
+                value,
+                lower_bound,
+                upper_bound,
+                ..
+            } => {
+                value.expect_type(ValueType::Integer)?;
+                lower_bound.expect_type(ValueType::Integer)?;
+                upper_bound.expect_type(ValueType::Integer)?;
+
+                Ok(ValueType::Boolean)
+            }
+        }
+    }
+
+    /// Returns `Err(...)` if this node does not evaluate to `expected_type`.
+    fn expect_type(&self, expected_type: ValueType) -> Result<(), PasswordPolicyExpressionError> {
+        let actual_type = self.check_type()?;
+
+        if actual_type == expected_type {
+            Ok(())
+        } else {
+            Err(PasswordPolicyExpressionError::new(
+                format!("Expected {}, but found {}", expected_type, actual_type),
+                self.span,
+            ))
+        }
+    }
+
+    /// Evaluates this node against the given `entry`.
+    ///
+    /// Assumes that `check_type()` has already succeeded.
+    fn evaluate(&self, entry: &PasswordDatabaseEntry) -> Value {
+        let PasswordDatabaseEntry {
+            password,
+            validation_metadata:
+                PasswordValidationMetadata {
+                    letter,
+                    parameters: (first_parameter, second_parameter),
+                },
+            ..
+        } = entry;
+
+        match &self.kind {
+            NodeKind::Integer(integer) => Value::Integer(*integer),
+            NodeKind::Character(character) => Value::Character(Some(character.clone())),
+            NodeKind::Boolean(boolean) => Value::Boolean(*boolean),
+            NodeKind::Letter => Value::Character(Some(letter.clone())),
+            NodeKind::FirstParameter => Value::Integer(*first_parameter),
+            NodeKind::SecondParameter => Value::Integer(*second_parameter),
+            NodeKind::Length(segmentation) => {
+                Value::Integer(segmentation.segment(password).len() as i64)
+            }
+            NodeKind::Count(segmentation, operand) => match operand.evaluate(entry) {
+                Value::Character(Some(counted_character)) => {
+                    Value::Integer(segmentation.count(&counted_character, password) as i64)
+                }
+                _ => Value::Integer(0),
+            },
+            NodeKind::Position(segmentation, operand) => Value::Character(
+                segmentation
+                    .unit_at(password, operand.evaluate_integer(entry))
+                    .map(str::to_owned),
+            ),
+            NodeKind::Not(operand) => Value::Boolean(!operand.evaluate_boolean(entry)),
+            NodeKind::Binary(operator, left_operand, right_operand) => {
+                Value::Boolean(match operator {
+                    BinaryOperator::And => {
+                        left_operand.evaluate_boolean(entry)
+                            && right_operand.evaluate_boolean(entry)
+                    }
+                    BinaryOperator::Xor => {
+                        left_operand.evaluate_boolean(entry)
+                            != right_operand.evaluate_boolean(entry)
+                    }
+                    BinaryOperator::Or => {
+                        left_operand.evaluate_boolean(entry)
+                            || right_operand.evaluate_boolean(entry)
+                    }
+                    BinaryOperator::Equals => {
+                        left_operand.evaluate(entry) == right_operand.evaluate(entry)
+                    }
+                    BinaryOperator::NotEquals => {
+                        left_operand.evaluate(entry) != right_operand.evaluate(entry)
+                    }
+                    BinaryOperator::Less => {
+                        left_operand.evaluate_integer(entry) < right_operand.evaluate_integer(entry)
+                    }
+                    BinaryOperator::LessEquals => {
+                        left_operand.evaluate_integer(entry)
+                            <= right_operand.evaluate_integer(entry)
+                    }
+                    BinaryOperator::Greater => {
+                        left_operand.evaluate_integer(entry) > right_operand.evaluate_integer(entry)
+                    }
+                    BinaryOperator::GreaterEquals => {
+                        left_operand.evaluate_integer(entry)
+                            >= right_operand.evaluate_integer(entry)
+                    }
+                })
+            }
+            NodeKind::InRange {
+                value,
+                lower_bound,
+                upper_bound,
+                is_inclusive,
+            } => {
+                let value = value.evaluate_integer(entry);
+                let lower_bound = lower_bound.evaluate_integer(entry);
+                let upper_bound = upper_bound.evaluate_integer(entry);
+
+                Value::Boolean(
+                    value >= lower_bound
+                        && if *is_inclusive {
+                            value <= upper_bound
+                        } else {
+                            value < upper_bound
+                        },
+                )
+            }
+        }
+    }
+
+    /// Evaluates this node, which must be a boolean, against `entry`.
+    fn evaluate_boolean(&self, entry: &PasswordDatabaseEntry) -> bool {
+        self.evaluate(entry) == Value::Boolean(true)
+    }
+
+    /// Evaluates this node, which must be an integer, against `entry`.
+    fn evaluate_integer(&self, entry: &PasswordDatabaseEntry) -> i64 {
+        match self.evaluate(entry) {
+            Value::Integer(integer) => integer,
+            _ => 0,
+        }
+    }
+}
+
+/// Turns tokens into a syntax tree by recursive descent.
+struct Parser {
+    /// Every token of the expression, ending with `TokenKind::End`.
+    tokens: Vec<Token>,
+    /// Index of the next token to consume.
+    position: usize,
+}
+
+impl Parser {
+    /// Returns the next token without consuming it.
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position.min(self.tokens.len() - 1)]
+    }
+
+    /// Consumes and returns the next token.
+    fn advance(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.position < self.tokens.len() - 1 {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    /// Consumes the next token, returning `Err(...)` if it is not of the
+    /// `expected` kind.
+    fn expect(&mut self, expected: &TokenKind) -> Result<Token, PasswordPolicyExpressionError> {
+        if self.peek().kind == *expected {
+            Ok(self.advance())
+        } else {
+            Err(self.unexpected_token_error(&expected.to_string()))
+        }
+    }
+
+    /// Creates an error complaining that the next token is not `expected`.
+    fn unexpected_token_error(&self, expected: &str) -> PasswordPolicyExpressionError {
+        let token = self.peek();
+
+        PasswordPolicyExpressionError::new(
+            format!("Expected {}, but found {}", expected, token.kind),
+            token.span,
+        )
+    }
+
+    /// `expression := xor ("||" xor)*`
+    fn parse_expression(&mut self) -> Result<Node, PasswordPolicyExpressionError> {
+        self.parse_binary_operations(BinaryOperator::Or, Parser::parse_xor)
+    }
+
+    /// `xor := and ("^" and)*`
+    fn parse_xor(&mut self) -> Result<Node, PasswordPolicyExpressionError> {
+        self.parse_binary_operations(BinaryOperator::Xor, Parser::parse_and)
+    }
+
+    /// `and := not ("&&" not)*`
+    fn parse_and(&mut self) -> Result<Node, PasswordPolicyExpressionError> {
+        self.parse_binary_operations(BinaryOperator::And, Parser::parse_not)
+    }
+
+    /// Parses a left-associative chain of `operator` applications, with
+    /// operands parsed by `parse_operand`.
+    fn parse_binary_operations(
+        &mut self,
+        operator: BinaryOperator,
+        parse_operand: fn(&mut Parser) -> Result<Node, PasswordPolicyExpressionError>,
+    ) -> Result<Node, PasswordPolicyExpressionError> {
+        let mut left_operand = parse_operand(self)?;
+
+        while BinaryOperator::from_token_kind(&self.peek().kind) == Some(operator) {
+            self.advance();
+
+            let right_operand = parse_operand(self)?;
+            let span = left_operand.span.to(right_operand.span);
+
+            left_operand = Node {
+                kind: NodeKind::Binary(operator, Box::new(left_operand), Box::new(right_operand)),
+                span,
+            };
+        }
+
+        Ok(left_operand)
+    }
+
+    /// `not := "!" not | comparison`
+    fn parse_not(&mut self) -> Result<Node, PasswordPolicyExpressionError> {
+        if self.peek().kind == TokenKind::Bang {
+            let bang = self.advance();
+            let operand = self.parse_not()?;
+            let span = bang.span.to(operand.span);
+
+            Ok(Node {
+                kind: NodeKind::Not(Box::new(operand)),
+                span,
+            })
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    /// `comparison := term (("==" | "!=" | "<" | "<=" | ">" | ">=") term | "in" term (".." | "..=") term)?`
+    fn parse_comparison(&mut self) -> Result<Node, PasswordPolicyExpressionError> {
+        let left_operand = self.parse_term()?;
+
+        if self.peek().kind == TokenKind::Identifier("in".to_owned()) {
+            self.advance();
+
+            let lower_bound = self.parse_term()?;
+            let is_inclusive = match self.peek().kind {
+                TokenKind::DoubleDotEquals => true,
+                TokenKind::DoubleDot => false,
+                _ => return Err(self.unexpected_token_error("`..` or `..=`")),
+            };
+            self.advance();
+            let upper_bound = self.parse_term()?;
+            let span = left_operand.span.to(upper_bound.span);
+
+            return Ok(Node {
+                kind: NodeKind::InRange {
+                    value: Box::new(left_operand),
+                    lower_bound: Box::new(lower_bound),
+                    upper_bound: Box::new(upper_bound),
+                    is_inclusive,
+                },
+                span,
+            });
+        }
+
+        match BinaryOperator::from_token_kind(&self.peek().kind) {
+            Some(
+                operator @ BinaryOperator::Equals
+                | operator @ BinaryOperator::NotEquals
+                | operator @ BinaryOperator::Less
+                | operator @ BinaryOperator::LessEquals
+                | operator @ BinaryOperator::Greater
+                | operator @ BinaryOperator::GreaterEquals,
+            ) => {
+                self.advance();
+
+                let right_operand = self.parse_term()?;
+                let span = left_operand.span.to(right_operand.span);
+
+                Ok(Node {
+                    kind: NodeKind::Binary(
+                        operator,
+                        Box::new(left_operand),
+                        Box::new(right_operand),
+                    ),
+                    span,
+                })
+            }
+            _ => Ok(left_operand),
+        }
+    }
+
+    /// `term := integer | character | identifier | identifier "(" arguments ")" | "(" expression ")"`
+    fn parse_term(&mut self) -> Result<Node, PasswordPolicyExpressionError> {
+        let token = self.advance();
+
+        let kind = match &token.kind {
+            TokenKind::Integer(integer) => NodeKind::Integer(*integer),
+            TokenKind::Character(character) => NodeKind::Character(character.clone()),
+            TokenKind::LeftParenthesis => {
+                let inner_node = self.parse_expression()?;
+                let right_parenthesis = self.expect(&TokenKind::RightParenthesis)?;
+
+                return Ok(Node {
+                    kind: inner_node.kind,
+                    span: token.span.to(right_parenthesis.span),
+                });
+            }
+            TokenKind::Identifier(identifier) => match identifier.as_str() {
+                "true" => NodeKind::Boolean(true),
+                "false" => NodeKind::Boolean(false),
+                "letter" => NodeKind::Letter,
+                "first" => NodeKind::FirstParameter,
+                "second" => NodeKind::SecondParameter,
+                "len" | "count" | "pos" | "glen" | "gcount" | "gpos" => {
+                    return self.parse_call(token.clone())
+                }
+                _ if identifier.graphemes(true).count() == 1 => {
+                    NodeKind::Character(identifier.clone())
+                }
+                _ => {
+                    return Err(PasswordPolicyExpressionError::new(
+                        format!("Unknown identifier `{}`", identifier),
+                        token.span,
+                    ))
+                }
+            },
+            _ => {
+                return Err(PasswordPolicyExpressionError::new(
+                    format!("Expected a value, but found {}", token.kind),
+                    token.span,
+                ))
+            }
+        };
+
+        Ok(Node {
+            kind,
+            span: token.span,
+        })
+    }
+
+    /// `call := ("len" | "count" | "pos" | "glen" | "gcount" | "gpos") "(" expression? ")"`
+    fn parse_call(&mut self, function: Token) -> Result<Node, PasswordPolicyExpressionError> {
+        self.expect(&TokenKind::LeftParenthesis)?;
+
+        let function_name = match &function.kind {
+            TokenKind::Identifier(identifier) => identifier.as_str(),
+            _ => "",
+        };
+        let segmentation = if function_name.starts_with('g') {
+            PasswordSegmentation::GraphemeClusters
+        } else {
+            PasswordSegmentation::CodePoints
+        };
+
+        let kind = match function_name {
+            "count" | "gcount" => NodeKind::Count(segmentation, Box::new(self.parse_expression()?)),
+            "pos" | "gpos" => NodeKind::Position(segmentation, Box::new(self.parse_expression()?)),
+            _ => NodeKind::Length(segmentation),
+        };
+
+        let right_parenthesis = self.expect(&TokenKind::RightParenthesis)?;
+
+        Ok(Node {
+            kind,
+            span: function.span.to(right_parenthesis.span),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::password_database::PasswordDatabase;
+    use crate::password_policy::PasswordValidationStrategy;
+
+    /// Example password database from https://adventofcode.com/2020/day/2.
+    const EXAMPLE_PASSWORD_DATABASE: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+    /// Asserts that `source` fails to parse, blaming the byte range from
+    /// `start` to `end`.
+    fn assert_error_span(source: &str, start: usize, end: usize) {
+        let error = PasswordPolicyExpression::parse(source).unwrap_err();
+
+        assert_eq!(error.span, Span { start, end }, "{}", error);
+    }
+
+    #[test]
+    fn agrees_with_existing_strategies() {
+        let password_database = PasswordDatabase::from_file(EXAMPLE_PASSWORD_DATABASE).unwrap();
+
+        for (source, strategy, expected_validities) in [
+            (
+                "count(letter) in first..=second",
+                PasswordValidationStrategy::LetterRepetitionRange,
+                [true, false, true],
+            ),
+            (
+                "pos(first) == letter ^ pos(second) == letter",
+                PasswordValidationStrategy::LetterPositions,
+                [true, false, false],
+            ),
+        ] {
+            let expression = PasswordPolicyExpression::parse(source).unwrap();
+
+            for (entry, expected_validity) in
+                password_database.entries.iter().zip(expected_validities)
+            {
+                assert_eq!(entry.is_valid(&strategy), expected_validity, "{}", entry);
+                assert_eq!(entry.is_valid(&expression), expected_validity, "{}", entry);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_combined_expression() {
+        let expression =
+            PasswordPolicyExpression::parse("count(a) in 1..=3 && (pos(1)==a ^ pos(3)==a)")
+                .unwrap();
+        let password_database =
+            PasswordDatabase::from_file("0-0 z: abcde\n0-0 z: abade\n0-0 z: bbbbb\n").unwrap();
+
+        let validities = password_database
+            .entries
+            .iter()
+            .map(|entry| entry.is_valid(&expression))
+            .collect::<Vec<bool>>();
+
+        assert_eq!(validities, [true, false, false]);
+    }
+
+    #[test]
+    fn blames_unclosed_quote() {
+        assert_error_span("count('a) in 1..=3", 6, 18);
+    }
+
+    #[test]
+    fn blames_operand_of_wrong_type() {
+        assert_error_span("count(1) in 1..=3", 6, 7);
+    }
+
+    #[test]
+    fn blames_missing_range_operator() {
+        assert_error_span("count(a) in 1 3", 14, 15);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::password_database::PasswordDatabaseEntry;
+use crate::password_policy::PasswordPolicy;
+
+/// Cross-tabulates how the entries of a password database are judged by an
+/// old password policy and by the new policy replacing it.
+#[derive(Debug)]
+pub struct PasswordPolicyMigration<'a> {
+    /// Entries valid under both the old and the new policy.
+    pub valid_under_both: Vec<&'a PasswordDatabaseEntry>,
+    /// Entries valid under the old policy, but not the new one: these are the
+    /// passwords that break because of the migration.
+    pub valid_under_old_only: Vec<&'a PasswordDatabaseEntry>,
+    /// Entries valid under the new policy, but not the old one.
+    pub valid_under_new_only: Vec<&'a PasswordDatabaseEntry>,
+    /// Entries valid under neither the old nor the new policy.
+    pub valid_under_neither: Vec<&'a PasswordDatabaseEntry>,
+}
+
+impl<'a> PasswordPolicyMigration<'a> {
+    /// Sorts each of the `entries` into the bucket matching how it is judged
+    /// by `old_policy` and `new_policy`, preserving their order.
+    pub fn new<O, N>(
+        entries: &'a [PasswordDatabaseEntry],
+        old_policy: &O,
+        new_policy: &N,
+    ) -> PasswordPolicyMigration<'a>
+    where
+        O: PasswordPolicy + ?Sized,
+        N: PasswordPolicy + ?Sized,
+    {
+        let mut migration = PasswordPolicyMigration {
+            valid_under_both: vec![],
+            valid_under_old_only: vec![],
+            valid_under_new_only: vec![],
+            valid_under_neither: vec![],
+        };
+
+        for entry in entries {
+            let bucket = match (entry.is_valid(old_policy), entry.is_valid(new_policy)) {
+                (true, true) => &mut migration.valid_under_both,
+                (true, false) => &mut migration.valid_under_old_only,
+                (false, true) => &mut migration.valid_under_new_only,
+                (false, false) => &mut migration.valid_under_neither,
+            };
+
+            bucket.push(entry);
+        }
+
+        migration
+    }
+
+    /// Counts the entries whose validity changes because of the migration.
+    pub fn number_of_affected_entries(&self) -> usize {
+        self.valid_under_old_only.len() + self.valid_under_new_only.len()
+    }
+}